@@ -0,0 +1,126 @@
+//! Compares the broad phases on thousands of circles piled into a box: how many candidate pairs
+//! each finds and how long that takes on its own, then the whole physics step with each. They
+//! should all find the same pairs, with [`BroadPhase::AllPairs`] the O(n²) baseline.
+//!
+//! `cargo bench --no-default-features --bench broad_phase -- 4000`
+
+use std::time::{Duration, Instant};
+
+use bevy::{
+    math::{Isometry2d, bounding::Aabb2d},
+    prelude::*,
+};
+use physics_project::{
+    BroadPhase, HeadlessSimulation, PhysicsPlugin,
+    components::{DynamicObject, Shape, StaticObject},
+};
+
+const RADIUS: f32 = 5.0;
+const STEPS: u32 = 120;
+/// Broad phase runs timed on the settled pile, per broad phase.
+const RUNS: u32 = 20;
+
+const BROAD_PHASES: [BroadPhase; 3] = [
+    BroadPhase::AllPairs,
+    BroadPhase::SweepAndPrune,
+    BroadPhase::UniformGrid {
+        cell_size: 4.0 * RADIUS,
+    },
+];
+
+fn main() {
+    let count: usize = std::env::args()
+        .skip(1)
+        .find(|arg| !arg.starts_with('-'))
+        .map(|count| count.parse().expect("circle count should be a number"))
+        .unwrap_or(2000);
+
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    spawn_scene(&mut sim, count);
+    sim.step(STEPS);
+    let boxes = bounding_boxes(&mut sim);
+    println!(
+        "Broad phase alone, {} shapes after {STEPS} steps:",
+        boxes.len()
+    );
+    for broad_phase in BROAD_PHASES {
+        let mut pairs = 0;
+        let elapsed = time(RUNS, || pairs = broad_phase.candidate_pairs(&boxes).len());
+        println!(
+            "  {broad_phase:?}: {pairs} candidate pairs, {:.3} ms",
+            per_run(elapsed, RUNS)
+        );
+    }
+
+    println!("Whole step, {count} circles:");
+    for broad_phase in BROAD_PHASES {
+        let mut sim =
+            HeadlessSimulation::new(PhysicsPlugin::default().with_broad_phase(broad_phase));
+        spawn_scene(&mut sim, count);
+        let elapsed = time(1, || sim.step(STEPS));
+        println!("  {broad_phase:?}: {:.2} ms/step", per_run(elapsed, STEPS));
+    }
+}
+
+fn time(runs: u32, mut run: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    for _ in 0..runs {
+        run();
+    }
+    start.elapsed()
+}
+
+fn per_run(elapsed: Duration, runs: u32) -> f64 {
+    elapsed.as_secs_f64() * 1000.0 / runs as f64
+}
+
+/// The boxes the collision step hands to the broad phase.
+fn bounding_boxes(sim: &mut HeadlessSimulation) -> Vec<Aabb2d> {
+    sim.world_mut()
+        .query::<(&Transform, &Shape)>()
+        .iter(sim.world())
+        .map(|(transform, shape)| {
+            let angle = transform.rotation.to_euler(EulerRot::ZYX).0;
+            shape.aabb(Isometry2d::new(
+                transform.translation.xy(),
+                Rot2::radians(angle),
+            ))
+        })
+        .collect()
+}
+/// A square grid of circles, slightly staggered so the pile doesn't stack in columns, above a
+/// floor between two walls.
+fn spawn_scene(sim: &mut HeadlessSimulation, count: usize) {
+    let columns = (count as f32).sqrt().ceil() as usize;
+    let width = columns as f32 * RADIUS * 3.0;
+    for (size, position) in [
+        (Vec2::new(width + 100.0, 50.0), Vec2::new(0.0, -25.0)),
+        (
+            Vec2::new(50.0, 4.0 * width),
+            Vec2::new(-width / 2.0 - 25.0, 2.0 * width),
+        ),
+        (
+            Vec2::new(50.0, 4.0 * width),
+            Vec2::new(width / 2.0 + 25.0, 2.0 * width),
+        ),
+    ] {
+        sim.world_mut().spawn((
+            Shape::Rect(size.x, size.y),
+            Transform::from_translation(position.extend(0.)),
+            StaticObject {},
+        ));
+    }
+    for i in 0..count {
+        let (row, column) = (i / columns, i % columns);
+        let stagger = if row % 2 == 0 { 0.0 } else { RADIUS };
+        let position = Vec2::new(
+            -width / 2.0 + (column as f32 + 0.5) * RADIUS * 3.0 + stagger - RADIUS / 2.0,
+            RADIUS * 2.0 + row as f32 * RADIUS * 3.0,
+        );
+        sim.world_mut().spawn((
+            Shape::Circle(RADIUS),
+            DynamicObject::new(1.0),
+            Transform::from_translation(position.extend(0.)),
+        ));
+    }
+}
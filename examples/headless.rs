@@ -0,0 +1,47 @@
+//! Drops a ball onto the floor without opening a window and prints where it ends up.
+//!
+//! `cargo run --no-default-features --example headless -- 600`
+
+use bevy::prelude::*;
+use physics_project::{
+    HeadlessSimulation, PhysicsPlugin,
+    components::{DynamicObject, Shape, StaticObject},
+};
+
+fn main() {
+    let steps: u32 = std::env::args()
+        .nth(1)
+        .map(|steps| steps.parse().expect("step count should be a number"))
+        .unwrap_or(600);
+
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    sim.world_mut().spawn((
+        Shape::Rect(10000.0, 50.),
+        Transform::from_xyz(0., -500.0, 0.),
+        StaticObject {},
+    ));
+    let ball = sim
+        .world_mut()
+        .spawn((
+            Shape::Circle(50.0),
+            DynamicObject::new(5.0),
+            Transform::default(),
+        ))
+        .id();
+
+    for step in 1..=steps {
+        sim.step(1);
+        if step % 60 == 0 || step == steps {
+            print_ball(&sim, ball);
+        }
+    }
+}
+
+fn print_ball(sim: &HeadlessSimulation, ball: Entity) {
+    let position = sim.world().get::<Transform>(ball).unwrap().translation.xy();
+    let velocity = sim.world().get::<DynamicObject>(ball).unwrap().velocity;
+    println!(
+        "t={:.3}s position={position} velocity={velocity}",
+        sim.elapsed().as_secs_f32()
+    );
+}
@@ -0,0 +1,74 @@
+//! A planet on a stable circular orbit around a star, held by n-body gravity alone.
+//!
+//! `cargo run --example orbit`
+
+use bevy::prelude::*;
+use physics_project::{
+    Integrator, NBodyGravity, PhysicsPlugin,
+    components::{DynamicObject, Shape},
+};
+
+const G: f32 = 1.0e6;
+const STAR_MASS: f32 = 100.0;
+const PLANET_MASS: f32 = 1.0;
+const RADIUS: f32 = 300.0;
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            PhysicsPlugin::default()
+                .with_gravity(Vec2::ZERO)
+                .with_integrator(Integrator::VelocityVerlet)
+                .with_n_body_gravity(NBodyGravity::new(G)),
+        ))
+        .add_systems(Startup, setup)
+        .add_systems(Update, draw_orbit)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    commands.spawn(Camera2d);
+
+    // Both bodies circle their centre of mass, at the origin, so neither drifts off.
+    let total = STAR_MASS + PLANET_MASS;
+    let speed = (G * total / RADIUS).sqrt();
+    for (mass, radius, color, side) in [
+        (
+            STAR_MASS,
+            30.0,
+            Color::srgb(1.0, 0.8, 0.2),
+            -PLANET_MASS / total,
+        ),
+        (
+            PLANET_MASS,
+            8.0,
+            Color::srgb(0.3, 0.5, 1.0),
+            STAR_MASS / total,
+        ),
+    ] {
+        let mut dynamic_object = DynamicObject::new(mass);
+        dynamic_object.velocity = Vec2::new(0.0, speed * side);
+        commands.spawn((
+            Shape::Circle(radius),
+            dynamic_object,
+            Transform::from_xyz(RADIUS * side, 0.0, 0.0),
+            Mesh2d(meshes.add(Circle::new(radius))),
+            MeshMaterial2d(materials.add(color)),
+        ));
+    }
+}
+
+/// Marks the circle the planet should stay on.
+fn draw_orbit(mut gizmos: Gizmos) {
+    let total = STAR_MASS + PLANET_MASS;
+    gizmos.circle_2d(
+        Isometry2d::IDENTITY,
+        RADIUS * STAR_MASS / total,
+        Color::srgb(0.3, 0.3, 0.3),
+    );
+}
@@ -0,0 +1,175 @@
+use bevy::{
+    math::bounding::{Aabb2d, IntersectsVolume},
+    prelude::*,
+    utils::HashMap,
+};
+
+/// How the collision step finds the pairs of shapes worth testing for contact.
+///
+/// All of them only pair shapes whose bounding boxes overlap, so they find the same contacts
+/// and differ only in speed.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum BroadPhase {
+    /// Sorts bounding boxes along x and pairs those whose extents overlap. Needs no tuning.
+    #[default]
+    SweepAndPrune,
+    /// Buckets bounding boxes into square cells `cell_size` wide and pairs those sharing a cell.
+    /// Fastest with cells a little larger than the typical shape.
+    ///
+    /// `cell_size` must be positive and finite. Cells much smaller than the shapes put each
+    /// shape in a great many cells, which slows the step to a crawl.
+    UniformGrid { cell_size: f32 },
+    /// Checks every box against every other. Far too slow beyond a few hundred shapes, but the
+    /// baseline the others are measured against.
+    AllPairs,
+}
+impl BroadPhase {
+    /// Panics if the settings can't find any pairs, like a [`BroadPhase::UniformGrid`] whose
+    /// cells have no size.
+    pub(crate) fn validate(&self) {
+        if let BroadPhase::UniformGrid { cell_size } = self {
+            assert!(
+                *cell_size > 0.0 && cell_size.is_finite(),
+                "uniform grid cell size should be positive and finite, not {cell_size}"
+            );
+        }
+    }
+
+    /// Index pairs `(i, j)` with `i < j` whose boxes overlap, each once and in order.
+    pub fn candidate_pairs(&self, boxes: &[Aabb2d]) -> Vec<(usize, usize)> {
+        // The config can be swapped at any time, not only through the plugin.
+        self.validate();
+        let mut pairs = match self {
+            BroadPhase::SweepAndPrune => sweep_and_prune(boxes),
+            BroadPhase::UniformGrid { cell_size } => uniform_grid(boxes, *cell_size),
+            BroadPhase::AllPairs => all_pairs(boxes),
+        };
+        // Contacts are solved one pair at a time, so keep the order the same whichever way the
+        // pairs were found.
+        pairs.sort_unstable();
+        pairs.dedup();
+        pairs
+    }
+}
+
+fn ordered(i: usize, j: usize) -> (usize, usize) {
+    (i.min(j), i.max(j))
+}
+
+fn sweep_and_prune(boxes: &[Aabb2d]) -> Vec<(usize, usize)> {
+    let mut order: Vec<usize> = (0..boxes.len()).collect();
+    order.sort_unstable_by(|&i, &j| boxes[i].min.x.total_cmp(&boxes[j].min.x));
+
+    let mut pairs = Vec::new();
+    let mut active: Vec<usize> = Vec::new();
+    for i in order {
+        active.retain(|&j| boxes[j].max.x >= boxes[i].min.x);
+        pairs.extend(
+            active
+                .iter()
+                .filter(|&&j| boxes[i].intersects(&boxes[j]))
+                .map(|&j| ordered(i, j)),
+        );
+        active.push(i);
+    }
+    pairs
+}
+
+fn uniform_grid(boxes: &[Aabb2d], cell_size: f32) -> Vec<(usize, usize)> {
+    let cell = |point: Vec2| (point / cell_size).floor().as_ivec2();
+    let mut cells: HashMap<IVec2, Vec<usize>> = HashMap::default();
+    for (i, aabb) in boxes.iter().enumerate() {
+        let (low, high) = (cell(aabb.min), cell(aabb.max));
+        for x in low.x..=high.x {
+            for y in low.y..=high.y {
+                cells.entry(IVec2::new(x, y)).or_default().push(i);
+            }
+        }
+    }
+
+    let mut pairs = Vec::new();
+    for members in cells.values() {
+        for (n, &i) in members.iter().enumerate() {
+            pairs.extend(
+                members[n + 1..]
+                    .iter()
+                    .filter(|&&j| boxes[i].intersects(&boxes[j]))
+                    .map(|&j| ordered(i, j)),
+            );
+        }
+    }
+    pairs
+}
+
+fn all_pairs(boxes: &[Aabb2d]) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    for i in 0..boxes.len() {
+        pairs.extend(
+            (i + 1..boxes.len())
+                .filter(|&j| boxes[i].intersects(&boxes[j]))
+                .map(|j| (i, j)),
+        );
+    }
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A deterministic scatter of boxes of mixed sizes, some overlapping.
+    fn scatter() -> Vec<Aabb2d> {
+        (0..200)
+            .map(|i| {
+                let i = i as f32;
+                let center = Vec2::new((i * 37.0) % 500.0, (i * 91.0) % 300.0);
+                let half_size = Vec2::new(5.0 + i % 7.0 * 3.0, 5.0 + i % 5.0 * 4.0);
+                Aabb2d::new(center, half_size)
+            })
+            .chain([Aabb2d::new(Vec2::new(250.0, -10.0), Vec2::new(400.0, 15.0))])
+            .collect()
+    }
+
+    #[test]
+    fn all_pairs_pairs_only_overlapping_boxes() {
+        let boxes = [
+            Aabb2d::new(Vec2::ZERO, Vec2::splat(10.0)),
+            Aabb2d::new(Vec2::new(100.0, 0.0), Vec2::splat(10.0)),
+            Aabb2d::new(Vec2::new(15.0, 5.0), Vec2::splat(10.0)),
+            Aabb2d::new(Vec2::new(0.0, 15.0), Vec2::splat(10.0)),
+        ];
+        assert_eq!(
+            BroadPhase::AllPairs.candidate_pairs(&boxes),
+            [(0, 2), (0, 3), (2, 3)]
+        );
+    }
+
+    #[test]
+    fn sweep_and_prune_finds_every_overlap() {
+        let boxes = scatter();
+        assert_eq!(
+            BroadPhase::SweepAndPrune.candidate_pairs(&boxes),
+            all_pairs(&boxes)
+        );
+    }
+
+    #[test]
+    fn uniform_grid_finds_every_overlap() {
+        let boxes = scatter();
+        for cell_size in [7.0, 40.0, 1000.0] {
+            assert_eq!(
+                BroadPhase::UniformGrid { cell_size }.candidate_pairs(&boxes),
+                all_pairs(&boxes)
+            );
+        }
+    }
+
+    #[test]
+    fn uniform_grid_rejects_cells_without_size() {
+        for cell_size in [0.0, -10.0, f32::NAN, f32::INFINITY] {
+            let grid = BroadPhase::UniformGrid { cell_size };
+            let result = std::panic::catch_unwind(|| grid.candidate_pairs(&scatter()));
+            assert!(result.is_err(), "cell size {cell_size}");
+        }
+    }
+}
@@ -0,0 +1,445 @@
+use bevy::{math::bounding::IntersectsVolume, prelude::*};
+
+use crate::{
+    PhysicsConfig,
+    collision_events::Contacts,
+    components::{
+        CollisionLayers, DynamicObject, Field, FluidRegion, ForceLabel, KinematicObject,
+        PhysicsMaterial, Sensor, Shape,
+    },
+    contact::isometry,
+    sleep,
+};
+
+/// Approach speeds below this don't bounce, so resting objects settle instead of jittering.
+const BOUNCE_THRESHOLD: f32 = 50.0;
+/// Overlap left alone by position correction, so resting contacts stay touching.
+const PENETRATION_SLOP: f32 = 0.5;
+/// Fraction of the remaining overlap corrected each step.
+const POSITION_CORRECTION: f32 = 0.8;
+/// Below this tangential speed a contact counts as resting and static friction applies.
+const STATIC_FRICTION_SPEED: f32 = 1.0;
+
+/// How two touching materials behave together.
+struct ContactMaterial {
+    restitution: f32,
+    static_friction: f32,
+    dynamic_friction: f32,
+}
+impl ContactMaterial {
+    fn combine(a: &PhysicsMaterial, b: &PhysicsMaterial) -> Self {
+        Self {
+            restitution: a.restitution.max(b.restitution),
+            static_friction: (a.static_friction * b.static_friction).sqrt(),
+            dynamic_friction: (a.dynamic_friction * b.dynamic_friction).sqrt(),
+        }
+    }
+}
+
+type Object<'a> = (
+    Option<Mut<'a, DynamicObject>>,
+    Mut<'a, Transform>,
+    &'a Shape,
+    Option<&'a PhysicsMaterial>,
+    Option<&'a KinematicObject>,
+    Option<&'a CollisionLayers>,
+    Entity,
+);
+
+/// Shapes that collide, which is all of them but fluid and field regions and sensors.
+pub(crate) type Solid = (Without<FluidRegion>, Without<Field>, Without<Sensor>);
+type Objects<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Option<&'static mut DynamicObject>,
+        &'static mut Transform,
+        &'static Shape,
+        Option<&'static PhysicsMaterial>,
+        Option<&'static KinematicObject>,
+        Option<&'static CollisionLayers>,
+        Entity,
+    ),
+    Solid,
+>;
+
+/// Whether objects with these [`CollisionLayers`] touch, treating a missing one as
+/// [`CollisionLayers::ALL`].
+pub(crate) fn layers_interact(a: Option<&CollisionLayers>, b: Option<&CollisionLayers>) -> bool {
+    let all = &CollisionLayers::ALL;
+    a.unwrap_or(all).interacts_with(b.unwrap_or(all))
+}
+
+/// Mass properties of one side of a contact. Both inverses are zero for static and kinematic
+/// objects.
+#[derive(Clone, Copy)]
+struct Body {
+    center: Vec2,
+    inverse_mass: f32,
+    inverse_inertia: f32,
+}
+impl Body {
+    fn of(object: &Object) -> Self {
+        let center = object.1.translation.xy();
+        match &object.0 {
+            Some(dynamic_object) if !dynamic_object.is_sleeping() => {
+                let inertia = object.2.moment_of_inertia(dynamic_object.mass);
+                Self {
+                    center,
+                    inverse_mass: dynamic_object.inverse_mass(),
+                    inverse_inertia: if inertia > 0.0 { inertia.recip() } else { 0.0 },
+                }
+            }
+            // Held still, as [`DynamicObject::is_sleeping`] describes.
+            _ => Self {
+                center,
+                inverse_mass: 0.0,
+                inverse_inertia: 0.0,
+            },
+        }
+    }
+
+    /// Change in velocity at `to` caused by `impulse` at `from`. Works the same for forces and
+    /// accelerations.
+    fn effect(&self, impulse: Vec2, from: Vec2, to: Vec2) -> Vec2 {
+        impulse * self.inverse_mass
+            + (from - self.center).perp_dot(impulse)
+                * self.inverse_inertia
+                * (to - self.center).perp()
+    }
+}
+
+fn velocity_at(object: &Object, body: &Body, point: Vec2) -> Vec2 {
+    match (&object.0, object.4) {
+        (Some(dynamic_object), _) => dynamic_object.velocity_at_point(point, body.center),
+        (None, Some(kinematic_object)) => kinematic_object.velocity_at_point(point, body.center),
+        (None, None) => Vec2::ZERO,
+    }
+}
+fn acceleration_at(object: &Object, body: &Body, point: Vec2) -> Vec2 {
+    object.0.as_ref().map_or(Vec2::ZERO, |dynamic_object| {
+        let angular_acceleration = dynamic_object.net_torque(body.center) * body.inverse_inertia;
+        dynamic_object.net_force() * body.inverse_mass
+            + angular_acceleration * (point - body.center).perp()
+    })
+}
+fn apply_impulse(object: &mut Object, body: &Body, impulse: Vec2, point: Vec2) {
+    if let Some(dynamic_object) = &mut object.0 {
+        dynamic_object.velocity += impulse * body.inverse_mass;
+        dynamic_object.angular_velocity +=
+            (point - body.center).perp_dot(impulse) * body.inverse_inertia;
+    }
+}
+/// Whether `object` is a kinematic object in motion, which pushes whatever it touches.
+fn is_driven(object: &Object) -> bool {
+    object.4.is_some_and(|kinematic_object| {
+        kinematic_object.velocity != Vec2::ZERO || kinematic_object.angular_velocity != 0.0
+    })
+}
+/// Wakes `object` if it is asleep and `other` is moving.
+fn wake_if_hit(object: &mut Object, other: &Object) {
+    let hit = is_driven(other)
+        || other
+            .0
+            .as_ref()
+            .is_some_and(|other| !other.is_sleeping() && sleep::is_moving(other));
+    if let Some(dynamic_object) = &mut object.0
+        && hit
+        && dynamic_object.is_sleeping()
+    {
+        dynamic_object.wake_up();
+    }
+}
+fn add_force(object: &mut Object, force: Vec2, point: Vec2, label: ForceLabel) {
+    if let Some(dynamic_object) = &mut object.0
+        && !dynamic_object.is_sleeping()
+    {
+        dynamic_object.add_labeled_force_at_point(force, point, label);
+    }
+}
+
+/// Finds the non-negative push at each of a manifold's one or two points that leaves none of
+/// them moving into each other.
+///
+/// `k[i][j]` is how much a unit push at point `i` changes the approach rate `b[j]` at point
+/// `j`. Pushes are only ever positive, so a point that is already separating gets none.
+fn solve_normal(k: [[f32; 2]; 2], b: [f32; 2], count: usize) -> [f32; 2] {
+    let first_only = (-b[0] / k[0][0]).max(0.0);
+    if count == 1 {
+        return [first_only, 0.0];
+    }
+
+    let determinant = k[0][0] * k[1][1] - k[0][1] * k[1][0];
+    if determinant.abs() > f32::EPSILON {
+        let both = [
+            (k[0][1] * b[1] - k[1][1] * b[0]) / determinant,
+            (k[1][0] * b[0] - k[0][0] * b[1]) / determinant,
+        ];
+        if both[0] >= 0.0 && both[1] >= 0.0 {
+            return both;
+        }
+    }
+    if first_only > 0.0 && k[0][1] * first_only + b[1] >= 0.0 {
+        return [first_only, 0.0];
+    }
+    let second_only = (-b[1] / k[1][1]).max(0.0);
+    if second_only > 0.0 && k[1][0] * second_only + b[0] >= 0.0 {
+        return [0.0, second_only];
+    }
+    [0.0, 0.0]
+}
+
+fn per_point(points: &[Vec2], value: impl Fn(Vec2) -> Vec2) -> [Vec2; 2] {
+    let mut values = [Vec2::ZERO; 2];
+    for (value_at, point) in values.iter_mut().zip(points) {
+        *value_at = value(*point);
+    }
+    values
+}
+
+/// Rounds of alternating between normal and friction pushes, so each can settle against the
+/// other.
+const SOLVER_ITERATIONS: usize = 16;
+
+/// Finds the normal and friction push at each contact point, either as impulses against
+/// relative velocities or as forces against relative accelerations.
+///
+/// `relative` is the value at each point before this contact pushes, and the normal pushes
+/// bring its normal part up to at least `targets`. `friction` gets the point index and
+/// position, the tangential value the friction push should oppose and the normal push there.
+fn solve_contact(
+    bodies: [Body; 2],
+    points: &[Vec2],
+    normal: Vec2,
+    relative: [Vec2; 2],
+    targets: [f32; 2],
+    friction: impl Fn(usize, Vec2, Vec2, f32) -> Vec2,
+) -> [(f32, Vec2); 2] {
+    let effect = |push: Vec2, from: Vec2, to: Vec2| {
+        bodies[0].effect(push, from, to) + bodies[1].effect(push, from, to)
+    };
+    let mut k = [[1.0; 2]; 2];
+    for (i, from) in points.iter().enumerate() {
+        for (j, to) in points.iter().enumerate() {
+            k[i][j] = effect(normal, *from, *to).dot(normal);
+        }
+    }
+
+    let mut normal_pushes = [0.0; 2];
+    let mut friction_pushes = [Vec2::ZERO; 2];
+    for _ in 0..SOLVER_ITERATIONS {
+        let mut approach = [0.0; 2];
+        for (j, to) in points.iter().enumerate() {
+            let mut value = relative[j];
+            for (i, from) in points.iter().enumerate() {
+                value += effect(friction_pushes[i], *from, *to);
+            }
+            approach[j] = value.dot(normal) - targets[j];
+        }
+        normal_pushes = solve_normal(k, approach, points.len());
+
+        for (i, to) in points.iter().enumerate() {
+            let mut value = relative[i];
+            for (j, from) in points.iter().enumerate() {
+                value += effect(normal * normal_pushes[j], *from, *to);
+                if j != i {
+                    value += effect(friction_pushes[j], *from, *to);
+                }
+            }
+            let slip = value - normal * value.dot(normal);
+            friction_pushes[i] = friction(i, *to, slip, normal_pushes[i]);
+        }
+    }
+    [
+        (normal_pushes[0], friction_pushes[0]),
+        (normal_pushes[1], friction_pushes[1]),
+    ]
+}
+
+/// Resolves every touching pair where at least one side is a [`DynamicObject`] and their
+/// [`CollisionLayers`] interact. Only pairs the [`PhysicsConfig::broad_phase`] finds are tested
+/// for contact.
+///
+/// Approaching objects get impulses at the contact points along the contact normal, scaled by
+/// the combined restitution, plus friction impulses bounded by Coulomb's law. Objects pressed
+/// together get normal forces cancelling the push and friction forces resisting sliding. All of
+/// these act at the contact points, so off-centre contacts spin objects. A [`KinematicObject`]
+/// pushes as if infinitely heavy, passing on the velocity of its surface.
+pub(crate) fn resolve_contacts(
+    mut objects: Objects,
+    mut contacts: ResMut<Contacts>,
+    config: Res<PhysicsConfig>,
+    time: Res<Time<Fixed>>,
+) {
+    contacts.start_step();
+    let dt = time.delta_secs();
+    let mut objects: Vec<_> = objects.iter_mut().collect();
+    let boxes: Vec<_> = objects
+        .iter()
+        .map(|object| object.2.aabb(isometry(&object.1)))
+        .collect();
+    for (i, j) in config.broad_phase.candidate_pairs(&boxes) {
+        let (head, tail) = objects.split_at_mut(j);
+        let (a, b) = (&mut head[i], &mut tail[0]);
+        if !layers_interact(a.5, b.5) {
+            continue;
+        }
+        if Body::of(a).inverse_mass + Body::of(b).inverse_mass == 0.0
+            && !is_driven(a)
+            && !is_driven(b)
+        {
+            // Objects asleep where they touched are still touching.
+            if contacts.was_touching(a.6, b.6) {
+                contacts.add(a.6, b.6, 0.0);
+            }
+            continue;
+        }
+        let Some(manifold) = a.2.contact(isometry(&a.1), b.2, isometry(&b.1)) else {
+            continue;
+        };
+        wake_if_hit(a, b);
+        wake_if_hit(b, a);
+        let (a_body, b_body) = (Body::of(a), Body::of(b));
+        let inverse_mass_sum = a_body.inverse_mass + b_body.inverse_mass;
+        if inverse_mass_sum == 0.0 {
+            continue;
+        }
+        // Points from b towards a.
+        let normal = -manifold.normal;
+        let points = manifold.points();
+        let tangent_response = |point: Vec2, direction: Vec2| {
+            (a_body.effect(direction, point, point) + b_body.effect(direction, point, point))
+                .dot(direction)
+        };
+        let material = ContactMaterial::combine(
+            a.3.unwrap_or(&config.default_material),
+            b.3.unwrap_or(&config.default_material),
+        );
+
+        let velocities = per_point(points, |point| {
+            velocity_at(a, &a_body, point) - velocity_at(b, &b_body, point)
+        });
+        let targets = velocities.map(|velocity| {
+            let normal_speed = velocity.dot(normal);
+            if normal_speed < -BOUNCE_THRESHOLD {
+                -material.restitution * normal_speed
+            } else {
+                0.0
+            }
+        });
+        let impulses = solve_contact(
+            [a_body, b_body],
+            points,
+            normal,
+            velocities,
+            targets,
+            |_, point, slip, normal_impulse| {
+                let direction = slip.normalize_or_zero();
+                let stopping = slip.length() / tangent_response(point, direction);
+                let magnitude = if stopping <= material.static_friction * normal_impulse {
+                    stopping
+                } else {
+                    material.dynamic_friction * normal_impulse
+                };
+                -direction * magnitude
+            },
+        );
+        contacts.add(a.6, b.6, 0.0);
+        for (point, (normal_impulse, friction)) in points.iter().zip(impulses) {
+            contacts.add(a.6, b.6, normal_impulse);
+            let impulse = normal * normal_impulse + friction;
+            apply_impulse(a, &a_body, impulse, *point);
+            apply_impulse(b, &b_body, -impulse, *point);
+        }
+
+        let velocities = per_point(points, |point| {
+            velocity_at(a, &a_body, point) - velocity_at(b, &b_body, point)
+        });
+        // Points moving apart fast enough to part within the step need nothing holding them up,
+        // and a force there would carry the objects apart for free.
+        let held: Vec<_> = (0..points.len())
+            .filter(|&i| velocities[i].dot(normal) * dt <= manifold.depth)
+            .collect();
+        let held_points: Vec<_> = held.iter().map(|&i| points[i]).collect();
+        let accelerations = per_point(&held_points, |point| {
+            acceleration_at(a, &a_body, point) - acceleration_at(b, &b_body, point)
+        });
+        let forces = solve_contact(
+            [a_body, b_body],
+            &held_points,
+            normal,
+            accelerations,
+            [0.0; 2],
+            |i, point, slip, normal_force| {
+                let velocity = velocities[held[i]];
+                let sliding = velocity - normal * velocity.dot(normal);
+                if sliding.length() < STATIC_FRICTION_SPEED {
+                    let direction = slip.normalize_or_zero();
+                    let holding = slip.length() / tangent_response(point, direction);
+                    if holding <= material.static_friction * normal_force {
+                        -direction * holding
+                    } else {
+                        -direction * material.dynamic_friction * normal_force
+                    }
+                } else {
+                    // Never strong enough to reverse the slide within one step.
+                    let direction = sliding.normalize_or_zero();
+                    let stopping = sliding.length() / (dt * tangent_response(point, direction));
+                    -direction * (material.dynamic_friction * normal_force).min(stopping)
+                }
+            },
+        );
+        for (point, (normal_force, friction)) in held_points.iter().zip(forces) {
+            if normal_force > 0.0 {
+                contacts.add(a.6, b.6, normal_force * dt);
+                add_force(a, normal * normal_force, *point, ForceLabel::Normal);
+                add_force(b, -normal * normal_force, *point, ForceLabel::Normal);
+                add_force(a, friction, *point, ForceLabel::Friction);
+                add_force(b, -friction, *point, ForceLabel::Friction);
+            }
+        }
+
+        // Pushes the objects apart by most of the overlap, the lighter one further.
+        let separation =
+            (manifold.depth - PENETRATION_SLOP).max(0.0) * POSITION_CORRECTION * normal;
+        if a_body.inverse_mass > 0.0 {
+            a.1.translation += (separation * a_body.inverse_mass / inverse_mass_sum).extend(0.);
+        }
+        if b_body.inverse_mass > 0.0 {
+            b.1.translation -= (separation * b_body.inverse_mass / inverse_mass_sum).extend(0.);
+        }
+    }
+}
+
+/// Refills every [`Sensor`] with the solid shapes overlapping it whose [`CollisionLayers`]
+/// interact with its own.
+pub(crate) fn detect_overlaps(
+    mut sensors: Query<(
+        Entity,
+        &mut Sensor,
+        &Transform,
+        &Shape,
+        Option<&CollisionLayers>,
+    )>,
+    objects: Query<(Entity, &Transform, &Shape, Option<&CollisionLayers>), Solid>,
+    mut contacts: ResMut<Contacts>,
+) {
+    for (sensor_entity, mut sensor, transform, shape, layers) in &mut sensors {
+        let sensor_isometry = isometry(transform);
+        let bounds = shape.aabb(sensor_isometry);
+        let overlapping = objects
+            .iter()
+            .filter(|(_, object_transform, object_shape, object_layers)| {
+                let object_isometry = isometry(object_transform);
+                layers_interact(layers, *object_layers)
+                    && bounds.intersects(&object_shape.aabb(object_isometry))
+                    && shape.intersects(sensor_isometry, object_shape, object_isometry)
+            })
+            .map(|(entity, ..)| entity);
+        sensor.overlapping.clear();
+        sensor.overlapping.extend(overlapping);
+        for &entity in &sensor.overlapping {
+            contacts.add(sensor_entity, entity, 0.0);
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use bevy::{prelude::*, utils::HashMap};
+
+/// Sent the first step two entities touch: a [`DynamicObject`](crate::components::DynamicObject)
+/// and a shape it collides with, or a [`Sensor`](crate::components::Sensor) and a shape it
+/// notices.
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct CollisionStarted {
+    pub a: Entity,
+    pub b: Entity,
+    /// Of the impact, as in [`Contacts::impulse`].
+    pub impulse: f32,
+}
+
+/// Sent the first step two entities that were touching no longer do, including when either
+/// was despawned.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CollisionEnded {
+    pub a: Entity,
+    pub b: Entity,
+}
+
+/// Every pair of entities touching as of the last physics step.
+#[derive(Resource, Debug, Default)]
+pub struct Contacts {
+    touching: HashMap<(Entity, Entity), f32>,
+    touched: HashMap<(Entity, Entity), f32>,
+}
+impl Contacts {
+    /// Size of the push holding `a` and `b` apart over the last step, counting both the impulse
+    /// of an impact and the normal force of a resting contact, or `None` if they aren't
+    /// touching. Always zero for a sensor and for objects asleep on each other.
+    pub fn impulse(&self, a: Entity, b: Entity) -> Option<f32> {
+        self.touching.get(&pair(a, b)).copied()
+    }
+    pub fn is_touching(&self, a: Entity, b: Entity) -> bool {
+        self.touching.contains_key(&pair(a, b))
+    }
+    /// Each touching pair and its [`Contacts::impulse`], in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, Entity, f32)> + '_ {
+        self.touching
+            .iter()
+            .map(|(&(a, b), &impulse)| (a, b, impulse))
+    }
+
+    /// Forgets the contacts of the step before last, ready to record this one's.
+    pub(crate) fn start_step(&mut self) {
+        std::mem::swap(&mut self.touching, &mut self.touched);
+        self.touching.clear();
+    }
+    pub(crate) fn add(&mut self, a: Entity, b: Entity, impulse: f32) {
+        *self.touching.entry(pair(a, b)).or_default() += impulse;
+    }
+    /// Whether `a` and `b` were touching the step before this one.
+    pub(crate) fn was_touching(&self, a: Entity, b: Entity) -> bool {
+        self.touched.contains_key(&pair(a, b))
+    }
+}
+
+/// The same key whichever way round the pair is given.
+fn pair(a: Entity, b: Entity) -> (Entity, Entity) {
+    (a.min(b), a.max(b))
+}
+
+/// Compares this step's [`Contacts`] with the last step's to send the
+/// [`CollisionStarted`] and [`CollisionEnded`] events.
+pub(crate) fn send_collision_events(
+    contacts: Res<Contacts>,
+    mut started: EventWriter<CollisionStarted>,
+    mut ended: EventWriter<CollisionEnded>,
+) {
+    for (&(a, b), &impulse) in &contacts.touching {
+        if !contacts.touched.contains_key(&(a, b)) {
+            started.send(CollisionStarted { a, b, impulse });
+        }
+    }
+    for &(a, b) in contacts.touched.keys() {
+        if !contacts.touching.contains_key(&(a, b)) {
+            ended.send(CollisionEnded { a, b });
+        }
+    }
+}
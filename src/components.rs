@@ -1,112 +1,724 @@
-use bevy::{
-    math::bounding::{Aabb2d, BoundingCircle, IntersectsVolume},
-    prelude::*,
-};
-use ops::atan2;
+use bevy::prelude::*;
 
 #[derive(Debug, Component)]
 pub enum Shape {
     Circle(f32),
     Rect(f32, f32),
+    /// Convex polygon with vertices around the object's centre of mass, in either winding.
+    Polygon(Vec<Vec2>),
+    /// Radius and the length of the straight part between the two caps, upright like
+    /// [`Capsule2d`].
+    Capsule(f32, f32),
+    /// Line between two points. Gives no inertia about its own line, so give dynamic segments
+    /// some thickness with a capsule instead.
+    Segment(Vec2, Vec2),
 }
 
 impl Shape {
-    pub fn intersects(&self, position: Vec2, other: &Self, other_position: Vec2) -> bool {
+    /// Moment of inertia about the centre for a solid shape of the given mass.
+    pub fn moment_of_inertia(&self, mass: f32) -> f32 {
         match self {
-            Shape::Circle(radius) => {
-                let a = BoundingCircle::new(position, *radius);
-                match other {
-                    Shape::Circle(radius) => {
-                        a.intersects(&BoundingCircle::new(other_position, *radius))
-                    }
-                    Shape::Rect(width, height) => a.intersects(&Aabb2d::new(
-                        other_position,
-                        Vec2::new(width / 2.0, height / 2.0),
-                    )),
+            Shape::Circle(radius) => 0.5 * mass * radius * radius,
+            Shape::Rect(width, height) => mass * (width * width + height * height) / 12.0,
+            Shape::Polygon(vertices) => {
+                // Sum over the triangles fanning out from the centre.
+                let (mut area, mut second_moment) = (0.0, 0.0);
+                for (i, a) in vertices.iter().enumerate() {
+                    let b = vertices[(i + 1) % vertices.len()];
+                    let doubled_area = a.perp_dot(b);
+                    area += doubled_area;
+                    second_moment += doubled_area * (a.dot(*a) + a.dot(b) + b.dot(b));
                 }
-            }
-            Shape::Rect(width, height) => {
-                let a = Aabb2d::new(position, Vec2::new(width / 2.0, height / 2.0));
-                match other {
-                    Shape::Circle(radius) => {
-                        a.intersects(&BoundingCircle::new(other_position, *radius))
-                    }
-                    Shape::Rect(width, height) => a.intersects(&Aabb2d::new(
-                        other_position,
-                        Vec2::new(width / 2.0, height / 2.0),
-                    )),
+                if area == 0.0 {
+                    0.0
+                } else {
+                    mass * second_moment / (6.0 * area)
                 }
             }
+            Shape::Capsule(radius, length) => {
+                // The rectangle in the middle plus the two caps, which together make a disc.
+                let rect_area = 2.0 * radius * length;
+                let caps_area = std::f32::consts::PI * radius * radius;
+                let rect_mass = mass * rect_area / (rect_area + caps_area);
+                let caps_mass = mass - rect_mass;
+                let caps_offset = 4.0 * radius / (3.0 * std::f32::consts::PI);
+                rect_mass * (4.0 * radius * radius + length * length) / 12.0
+                    + caps_mass
+                        * (radius * radius / 2.0 + length * length / 4.0 + length * caps_offset)
+            }
+            Shape::Segment(start, end) => {
+                let middle = (*start + *end) / 2.0;
+                mass * (start.distance_squared(*end) / 12.0 + middle.length_squared())
+            }
         }
     }
 
-    pub(crate) fn closest_point(
+    /// Whether this shape overlaps `other`. Rectangles are turned by the rotation of their
+    /// isometry.
+    pub fn intersects(
         &self,
-        position: Vec2,
-        _other: &Shape,
-        other_position: Vec2,
-    ) -> Vec2 {
-        match self {
-            Shape::Circle(radius) => {
-                let a = BoundingCircle::new(position, *radius);
-                a.closest_point(other_position)
-            }
-            Shape::Rect(width, height) => {
-                let a = Aabb2d::new(position, Vec2::new(width / 2.0, height / 2.0));
-                a.closest_point(other_position)
-            }
+        isometry: impl Into<Isometry2d>,
+        other: &Self,
+        other_isometry: impl Into<Isometry2d>,
+    ) -> bool {
+        self.contact(isometry, other, other_isometry).is_some()
+    }
+}
+
+/// One end of a [`SpringJoint`] or [`RigidJoint`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JointAnchor {
+    /// The centre of an entity. Only a [`DynamicObject`] is moved by the joint; anything else
+    /// holds its end still.
+    Entity(Entity),
+    /// A fixed point in the world.
+    Point(Vec2),
+}
+impl From<Entity> for JointAnchor {
+    fn from(entity: Entity) -> Self {
+        Self::Entity(entity)
+    }
+}
+impl From<Vec2> for JointAnchor {
+    fn from(point: Vec2) -> Self {
+        Self::Point(point)
+    }
+}
+
+/// A damped spring between two anchors, spawned as an entity of its own.
+///
+/// The joint does nothing while either end is an entity that no longer exists, and can be
+/// despawned like any other entity.
+#[derive(Debug, Component, Clone, Copy, PartialEq)]
+pub struct SpringJoint {
+    pub a: JointAnchor,
+    pub b: JointAnchor,
+    /// Force per unit the joint is stretched or squashed past `rest_length`.
+    pub stiffness: f32,
+    /// Force per unit of speed the ends move apart or together, resisting that movement.
+    pub damping: f32,
+    pub rest_length: f32,
+    /// The ends are kept at least this far apart.
+    pub min_length: f32,
+    /// The ends are kept at most this far apart, like a rope.
+    pub max_length: f32,
+}
+impl SpringJoint {
+    pub fn new(
+        a: impl Into<JointAnchor>,
+        b: impl Into<JointAnchor>,
+        stiffness: f32,
+        rest_length: f32,
+    ) -> Self {
+        Self {
+            a: a.into(),
+            b: b.into(),
+            stiffness,
+            damping: 0.0,
+            rest_length,
+            min_length: 0.0,
+            max_length: f32::INFINITY,
         }
     }
+    pub fn with_damping(mut self, damping: f32) -> Self {
+        self.damping = damping;
+        self
+    }
+    pub fn with_length_limits(mut self, min_length: f32, max_length: f32) -> Self {
+        self.min_length = min_length;
+        self.max_length = max_length;
+        self
+    }
 }
-#[derive(Debug, Component)]
-pub struct SpringConstraint {
-    pub other: Entity,
-    pub strength: f32,
-    pub length: f32,
+
+/// Point-mass [`DynamicObject`]s held in shape by [`SpringJoint`]s, as spawned by a
+/// [`SoftBodyBuilder`](crate::SoftBodyBuilder).
+#[derive(Debug, Component, Clone)]
+pub struct SoftBody {
+    /// Points still in the world. Despawned ones are dropped at the end of the next step.
+    pub particles: Vec<Entity>,
+    pub springs: Vec<Entity>,
+    /// Counter-clockwise triangles covering the body, as indices into `particles`.
+    pub triangles: Vec<[u32; 3]>,
+}
+
+/// One of the points of a [`SoftBody`].
+#[derive(Debug, Component, Clone, Copy, PartialEq)]
+pub struct SoftBodyParticle {
+    pub body: Entity,
+}
+
+/// What a [`RigidJoint`] holds fixed between its two attachment points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RigidJointKind {
+    /// Keeps the points exactly this far apart, like a light rod.
+    Distance(f32),
+    /// Keeps the points at most this far apart, going slack when they are closer.
+    Rope(f32),
+    /// Holds the points together, leaving both ends free to turn about them like a hinge.
+    Pin,
+    /// Lets the points slide apart only along an axis fixed in `a`'s frame, and stops the ends
+    /// turning relative to each other.
+    Prismatic(Vec2),
+}
+
+/// A rigid link between two anchors, spawned as an entity of its own and enforced after every
+/// step has moved the objects.
+///
+/// Like a [`SpringJoint`], it does nothing while either end is an entity that no longer exists.
+#[derive(Debug, Component, Clone, Copy, PartialEq)]
+pub struct RigidJoint {
+    pub a: JointAnchor,
+    pub b: JointAnchor,
+    /// Where the joint attaches to `a`, from its centre and turning with it, or from the point
+    /// of a [`JointAnchor::Point`].
+    pub local_a: Vec2,
+    pub local_b: Vec2,
+    pub kind: RigidJointKind,
+    /// Rotation of `b` relative to `a` when a prismatic joint first took part in a step.
+    pub(crate) reference_angle: Option<f32>,
+}
+impl RigidJoint {
+    pub fn new(a: impl Into<JointAnchor>, b: impl Into<JointAnchor>, kind: RigidJointKind) -> Self {
+        Self {
+            a: a.into(),
+            b: b.into(),
+            local_a: Vec2::ZERO,
+            local_b: Vec2::ZERO,
+            kind,
+            reference_angle: None,
+        }
+    }
+    pub fn distance(a: impl Into<JointAnchor>, b: impl Into<JointAnchor>, length: f32) -> Self {
+        Self::new(a, b, RigidJointKind::Distance(length))
+    }
+    pub fn rope(a: impl Into<JointAnchor>, b: impl Into<JointAnchor>, length: f32) -> Self {
+        Self::new(a, b, RigidJointKind::Rope(length))
+    }
+    pub fn pin(a: impl Into<JointAnchor>, b: impl Into<JointAnchor>) -> Self {
+        Self::new(a, b, RigidJointKind::Pin)
+    }
+    pub fn prismatic(a: impl Into<JointAnchor>, b: impl Into<JointAnchor>, axis: Vec2) -> Self {
+        Self::new(a, b, RigidJointKind::Prismatic(axis))
+    }
+    /// Attaches the joint away from the centres of its ends.
+    pub fn with_local_anchors(mut self, local_a: Vec2, local_b: Vec2) -> Self {
+        self.local_a = local_a;
+        self.local_b = local_b;
+        self
+    }
 }
 
 #[derive(Debug, Component)]
 pub struct DynamicObject {
     pub velocity: Vec2,
+    /// Counter-clockwise, in radians per second.
+    pub angular_velocity: f32,
     pub mass: f32,
+    /// Forces acting on the object this step. Cleared at the start of every
+    /// [`PhysicsSet::ClearForces`](crate::PhysicsSet::ClearForces), so systems adding forces
+    /// should run in the [`PhysicsForces`](crate::PhysicsForces) schedule.
     pub forces: Vec<Force>,
+    /// Torque pushed this step on top of what off-centre [`Force`]s produce. Cleared along with
+    /// `forces`.
+    pub torque: f32,
+    pub(crate) last_acceleration: Option<Vec2>,
+    pub(crate) sleep: Sleep,
+}
+
+/// Whether an object is asleep, and what it needs to stay that way.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct Sleep {
+    /// How long the object has been resting while awake, in seconds.
+    pub(crate) resting_for: f32,
+    pub(crate) asleep: bool,
+    /// Force and torque from everything but contacts when it fell asleep. A change wakes it.
+    pub(crate) load: (Vec2, f32),
 }
 impl DynamicObject {
     pub fn new(mass: f32) -> Self {
         Self {
             velocity: Vec2::default(),
+            angular_velocity: 0.0,
             forces: Vec::default(),
+            torque: 0.0,
+            last_acceleration: None,
+            sleep: Sleep::default(),
             mass,
         }
     }
+
+    /// Pushes a force through the centre of mass.
+    pub fn add_force(&mut self, force: Vec2) {
+        self.add_labeled_force(force, ForceLabel::default());
+    }
+    /// Like [`DynamicObject::add_force`], tagged with where the force came from.
+    pub fn add_labeled_force(&mut self, force: Vec2, label: ForceLabel) {
+        self.forces.push(Force {
+            vector: force,
+            point: None,
+            label,
+        });
+    }
+    /// Pushes a force applied at `point`, in world space. Off-centre forces also spin the
+    /// object.
+    pub fn add_force_at_point(&mut self, force: Vec2, point: Vec2) {
+        self.add_labeled_force_at_point(force, point, ForceLabel::default());
+    }
+    pub fn add_labeled_force_at_point(&mut self, force: Vec2, point: Vec2, label: ForceLabel) {
+        self.forces.push(Force {
+            vector: force,
+            point: Some(point),
+            label,
+        });
+    }
+    /// Pushes a counter-clockwise torque.
+    pub fn add_torque(&mut self, torque: f32) {
+        self.torque += torque;
+    }
+    /// Pushes the force that gives this object `acceleration` whatever its mass.
+    pub fn add_acceleration(&mut self, acceleration: Vec2) {
+        self.add_force(acceleration * self.mass);
+    }
+    /// Changes the momentum of the object immediately, rather than over a step.
+    pub fn add_impulse(&mut self, impulse: Vec2) {
+        self.velocity += impulse / self.mass;
+    }
+
+    /// Sleeping objects have come to rest and are left out of the simulation until something
+    /// disturbs them. Meanwhile they hold still like static ones.
+    pub fn is_sleeping(&self) -> bool {
+        self.sleep.asleep
+    }
+    pub fn wake_up(&mut self) {
+        self.sleep = Sleep::default();
+    }
+
+    pub fn inverse_mass(&self) -> f32 {
+        self.mass.recip()
+    }
+
+    /// Sum of every force pushed this step.
+    pub fn net_force(&self) -> Vec2 {
+        self.forces.iter().map(|force| force.vector).sum()
+    }
+    /// Sum of every torque pushed this step, about the centre of mass at `center`.
+    pub fn net_torque(&self, center: Vec2) -> f32 {
+        self.torque
+            + self
+                .forces
+                .iter()
+                .filter_map(|force| Some((force.point? - center).perp_dot(force.vector)))
+                .sum::<f32>()
+    }
+    /// Velocity of the point of this object currently at `point`, given its centre.
+    pub fn velocity_at_point(&self, point: Vec2, center: Vec2) -> Vec2 {
+        self.velocity + self.angular_velocity * (point - center).perp()
+    }
 }
 
-#[derive(Debug, Component, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Force {
-    pub magnitude: f32,
+    pub vector: Vec2,
+    /// World-space point the force is applied at, or `None` for the centre of mass.
+    pub point: Option<Vec2>,
+    pub label: ForceLabel,
+}
+
+/// What produced a [`Force`], so debug views can tell forces apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ForceLabel {
+    /// Pushed by game code.
+    #[default]
+    Applied,
+    Gravity,
+    Spring,
+    Normal,
+    Friction,
+    Drag,
+    Buoyancy,
+    /// Pushed by [`Charge`]s and the electric part of [`Field`]s.
+    Electric,
+    Magnetic,
+    ForceField,
+}
+impl ForceLabel {
+    /// Whether the force is one objects push on each other where they touch.
+    pub fn is_contact(self) -> bool {
+        matches!(self, Self::Normal | Self::Friction)
+    }
+}
+
+#[derive(Debug, Component)]
+pub struct StaticObject {}
+
+/// Moved by game code rather than by forces, like a moving platform, a piston or a paddle.
+/// Collisions treat it as infinitely heavy: it shoves [`DynamicObject`]s out of its way and
+/// carries them along, and nothing pushes back.
+#[derive(Debug, Component, Clone, PartialEq, Default)]
+pub struct KinematicObject {
+    pub velocity: Vec2,
+    /// Counter-clockwise, in radians per second.
+    pub angular_velocity: f32,
+    /// Keyframes to follow, which set both velocities every step.
+    pub path: Option<KinematicPath>,
+}
+impl KinematicObject {
+    pub fn new(velocity: Vec2) -> Self {
+        Self {
+            velocity,
+            ..default()
+        }
+    }
+    pub fn with_angular_velocity(mut self, angular_velocity: f32) -> Self {
+        self.angular_velocity = angular_velocity;
+        self
+    }
+    pub fn with_path(mut self, path: KinematicPath) -> Self {
+        self.path = Some(path);
+        self
+    }
+    /// Velocity of the point of this object currently at `point`, given its centre.
+    pub fn velocity_at_point(&self, point: Vec2, center: Vec2) -> Vec2 {
+        self.velocity + self.angular_velocity * (point - center).perp()
+    }
+}
+
+/// Poses a [`KinematicObject`] passes through, moving in a straight line at a steady turn
+/// between them. Spawn the object at the first one, as it heads from wherever it is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KinematicPath {
+    /// In order of time.
+    pub keyframes: Vec<Keyframe>,
+    /// Whether to start again from the first keyframe after the last, rather than stop there.
+    /// Ending on the starting pose makes a smooth loop.
+    pub repeat: bool,
+    /// Seconds since the path started.
+    pub time: f32,
+}
+impl KinematicPath {
+    pub fn new(keyframes: Vec<Keyframe>) -> Self {
+        Self {
+            keyframes,
+            repeat: false,
+            time: 0.0,
+        }
+    }
+    pub fn repeating(mut self) -> Self {
+        self.repeat = true;
+        self
+    }
+
+    /// Position and angle the path is at `time` seconds in, or `None` without keyframes.
+    pub fn pose_at(&self, time: f32) -> Option<(Vec2, f32)> {
+        let (first, last) = (self.keyframes.first()?, self.keyframes.last()?);
+        let time = if self.repeat && last.time > first.time {
+            first.time + (time - first.time).rem_euclid(last.time - first.time)
+        } else {
+            time
+        };
+        let next = self
+            .keyframes
+            .iter()
+            .position(|keyframe| keyframe.time > time);
+        let (from, to) = match next {
+            Some(0) => return Some((first.position, first.angle)),
+            Some(next) => (self.keyframes[next - 1], self.keyframes[next]),
+            None => return Some((last.position, last.angle)),
+        };
+        let fraction = (time - from.time) / (to.time - from.time);
+        Some((
+            from.position.lerp(to.position, fraction),
+            from.angle + (to.angle - from.angle) * fraction,
+        ))
+    }
+}
+
+/// Where a [`KinematicPath`] has its object at `time` seconds in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Keyframe {
+    pub time: f32,
+    pub position: Vec2,
+    /// Counter-clockwise, in radians.
     pub angle: f32,
-    pub(crate) color: Option<Color>,
 }
-impl Force {
-    pub(crate) fn from_x_and_y(x: f32, y: f32, color: Option<Color>) -> Self {
+impl Keyframe {
+    pub fn new(time: f32, position: Vec2) -> Self {
         Self {
-            magnitude: (x.powi(2) + y.powi(2)).sqrt(),
-            angle: atan2(y, x),
-            color, // color:
+            time,
+            position,
+            angle: 0.0,
         }
     }
-    pub(crate) fn from_magnitude_and_angle(
-        magnitude: f32,
-        angle: f32,
-        color: Option<Color>,
-    ) -> Self {
+    pub fn with_angle(mut self, angle: f32) -> Self {
+        self.angle = angle;
+        self
+    }
+}
+
+/// Air resistance on a [`DynamicObject`], against its velocity.
+#[derive(Debug, Component, Clone, Copy, PartialEq)]
+pub struct Drag {
+    /// Force per unit of speed. Dominates for slow objects.
+    pub linear: f32,
+    /// Drag coefficient of the shape. The quadratic part of the force is `½ρ C w v²`, where `w`
+    /// is the [`Shape::width_across`] the direction of travel and `ρ` is
+    /// [`PhysicsConfig::air_density`](crate::PhysicsConfig::air_density).
+    pub coefficient: f32,
+}
+impl Drag {
+    pub fn new(linear: f32, coefficient: f32) -> Self {
         Self {
-            magnitude,
-            angle,
-            color,
+            linear,
+            coefficient,
         }
     }
 }
 
-#[derive(Debug, Component)]
-pub struct StaticObject {}
+/// Fluid filling the entity's [`Shape`], lifting and slowing the [`DynamicObject`]s in it.
+/// Other shapes pass through it rather than colliding.
+#[derive(Debug, Component, Clone, Copy, PartialEq)]
+pub struct FluidRegion {
+    /// Mass per unit area. Objects less dense than this float.
+    pub density: f32,
+    /// Force per unit of speed and unit of submerged area, against movement through the fluid.
+    pub viscosity: f32,
+}
+impl FluidRegion {
+    pub fn new(density: f32, viscosity: f32) -> Self {
+        Self { density, viscosity }
+    }
+}
+
+/// A drop of the [`ParticleFluid`](crate::ParticleFluid), moved by the pressure and viscosity
+/// of the drops around it rather than as a [`DynamicObject`]. Drops collide with static
+/// [`Shape`]s and pass through everything else.
+#[derive(Debug, Component, Clone, Copy, PartialEq, Default)]
+pub struct FluidParticle {
+    pub velocity: Vec2,
+    /// How crowded the drop was last step, in drops per unit area.
+    pub density: f32,
+}
+impl FluidParticle {
+    pub fn new(velocity: Vec2) -> Self {
+        Self {
+            velocity,
+            density: 0.0,
+        }
+    }
+}
+
+/// Electric charge of an entity. Charges push each other apart, or pull if their signs differ,
+/// scaled by [`PhysicsConfig::coulomb_constant`](crate::PhysicsConfig::coulomb_constant). Only
+/// [`DynamicObject`]s are moved, but any charged entity pushes them.
+#[derive(Debug, Component, Clone, Copy, PartialEq)]
+pub struct Charge(pub f32);
+
+/// Uniform electric and magnetic field filling the entity's [`Shape`], pushing on the charged
+/// [`DynamicObject`]s whose centres are inside it. Other shapes pass through it rather than
+/// colliding.
+#[derive(Debug, Component, Clone, Copy, PartialEq, Default)]
+pub struct Field {
+    /// Force per unit of charge.
+    pub electric: Vec2,
+    /// Strength of the magnetic field out of the screen. Positive fields turn moving positive
+    /// charges clockwise.
+    pub magnetic: f32,
+}
+impl Field {
+    pub fn new(electric: Vec2, magnetic: f32) -> Self {
+        Self { electric, magnetic }
+    }
+    pub fn electric(electric: Vec2) -> Self {
+        Self::new(electric, 0.0)
+    }
+    pub fn magnetic(magnetic: f32) -> Self {
+        Self::new(Vec2::ZERO, magnetic)
+    }
+}
+
+/// Pushes every [`DynamicObject`] whose centre is within `radius` of the entity's position.
+#[derive(Debug, Component, Clone, Copy, PartialEq)]
+pub struct ForceField {
+    pub kind: ForceFieldKind,
+    /// Force at the centre, in world units. Negative strengths push the other way.
+    pub strength: f32,
+    pub radius: f32,
+    pub falloff: Falloff,
+    /// Seconds left before the field is despawned, or `None` to keep it.
+    pub lifetime: Option<f32>,
+}
+impl ForceField {
+    pub fn new(kind: ForceFieldKind, strength: f32, radius: f32) -> Self {
+        Self {
+            kind,
+            strength,
+            radius,
+            falloff: Falloff::default(),
+            lifetime: None,
+        }
+    }
+    /// Pulls objects in towards the centre.
+    pub fn attractor(strength: f32, radius: f32) -> Self {
+        Self::new(ForceFieldKind::Radial, strength, radius)
+    }
+    /// Blasts objects away from the centre for a tenth of a second, weaker further out.
+    pub fn explosion(strength: f32, radius: f32) -> Self {
+        Self::new(ForceFieldKind::Radial, -strength, radius)
+            .with_falloff(Falloff::Linear)
+            .with_lifetime(0.1)
+    }
+    /// Blows objects along `direction`.
+    pub fn wind(direction: Vec2, strength: f32, radius: f32) -> Self {
+        Self::new(ForceFieldKind::Wind(direction), strength, radius)
+    }
+    /// Swirls objects counter-clockwise around the centre.
+    pub fn vortex(strength: f32, radius: f32) -> Self {
+        Self::new(ForceFieldKind::Vortex, strength, radius)
+    }
+    pub fn with_falloff(mut self, falloff: Falloff) -> Self {
+        self.falloff = falloff;
+        self
+    }
+    pub fn with_lifetime(mut self, lifetime: f32) -> Self {
+        self.lifetime = Some(lifetime);
+        self
+    }
+
+    /// Force on an object at `offset` from the centre, or `None` if it is out of range.
+    pub fn force_at(&self, offset: Vec2) -> Option<Vec2> {
+        let distance = offset.length();
+        if distance > self.radius {
+            return None;
+        }
+        let direction = match self.kind {
+            ForceFieldKind::Radial => -offset.normalize_or_zero(),
+            ForceFieldKind::Wind(direction) => direction.normalize_or_zero(),
+            ForceFieldKind::Vortex => offset.normalize_or_zero().perp(),
+        };
+        Some(direction * self.strength * self.falloff.scale(distance / self.radius))
+    }
+}
+
+/// Which way a [`ForceField`] pushes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ForceFieldKind {
+    /// Towards the centre.
+    Radial,
+    /// Along a direction in world space.
+    Wind(Vec2),
+    /// Counter-clockwise around the centre.
+    Vortex,
+}
+
+/// How a [`ForceField`] weakens from its centre to its edge.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Falloff {
+    /// Full strength all the way out.
+    #[default]
+    Constant,
+    /// Down to nothing at the edge in a straight line.
+    Linear,
+    /// Like [`Falloff::Linear`] squared, fading quickly away from the centre.
+    Quadratic,
+}
+impl Falloff {
+    /// Fraction of the strength left `fraction` of the way from the centre to the edge.
+    pub fn scale(self, fraction: f32) -> f32 {
+        let left = (1.0 - fraction).clamp(0.0, 1.0);
+        match self {
+            Falloff::Constant => 1.0,
+            Falloff::Linear => left,
+            Falloff::Quadratic => left * left,
+        }
+    }
+}
+
+/// Multiplies the [`Gravity`](crate::Gravity) a [`DynamicObject`] feels. Zero floats, and
+/// negative scales rise like a balloon.
+#[derive(Debug, Component, Clone, Copy, PartialEq)]
+pub struct GravityScale(pub f32);
+impl Default for GravityScale {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Sweeps a circular [`DynamicObject`] along its path each step, so it can't pass through
+/// static shapes between steps however fast it goes.
+#[derive(Debug, Component, Default)]
+pub struct ContinuousCollision {
+    /// Where the object was before this step moved it.
+    pub(crate) start: Vec2,
+}
+
+/// Surface properties used when this object touches another.
+///
+/// Objects without one use [`PhysicsConfig::default_material`](crate::PhysicsConfig).
+#[derive(Debug, Component, Clone, Copy, PartialEq)]
+pub struct PhysicsMaterial {
+    /// Fraction of the approach speed kept when bouncing. The larger of the two objects' values
+    /// is used.
+    pub restitution: f32,
+    /// Friction coefficient holding a resting contact in place.
+    pub static_friction: f32,
+    /// Friction coefficient opposing a sliding contact.
+    pub dynamic_friction: f32,
+}
+impl PhysicsMaterial {
+    pub fn new(restitution: f32, static_friction: f32, dynamic_friction: f32) -> Self {
+        Self {
+            restitution,
+            static_friction,
+            dynamic_friction,
+        }
+    }
+}
+impl Default for PhysicsMaterial {
+    fn default() -> Self {
+        Self::new(0.8, 0.5, 0.3)
+    }
+}
+
+/// Groups an object belongs to and groups it collides with, one bit per group. Two objects
+/// touch only if each is in a group the other collides with.
+///
+/// Objects without one are in every group and collide with every group.
+#[derive(Debug, Component, Clone, Copy, PartialEq, Eq)]
+pub struct CollisionLayers {
+    pub memberships: u32,
+    pub filters: u32,
+}
+impl CollisionLayers {
+    pub const ALL: Self = Self::new(u32::MAX, u32::MAX);
+    /// Touches nothing, like a ghost.
+    pub const NONE: Self = Self::new(0, 0);
+
+    pub const fn new(memberships: u32, filters: u32) -> Self {
+        Self {
+            memberships,
+            filters,
+        }
+    }
+    pub fn interacts_with(&self, other: &Self) -> bool {
+        self.memberships & other.filters != 0 && other.memberships & self.filters != 0
+    }
+}
+impl Default for CollisionLayers {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+/// Turns a shape into a trigger volume, like a goal zone: it records what overlaps it instead
+/// of colliding. [`CollisionLayers`] choose what it notices. Sensors don't notice each other.
+#[derive(Debug, Component, Clone, Default, PartialEq)]
+pub struct Sensor {
+    /// Entities whose shapes overlapped this one at the end of the last physics step.
+    pub overlapping: Vec<Entity>,
+}
+impl Sensor {
+    pub fn is_overlapping(&self, entity: Entity) -> bool {
+        self.overlapping.contains(&entity)
+    }
+}
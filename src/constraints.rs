@@ -0,0 +1,329 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    components::{DynamicObject, JointAnchor, RigidJoint, RigidJointKind, Shape},
+    sleep,
+};
+
+/// Passes over every joint removing velocity that breaks it. More make long chains stiffer.
+const VELOCITY_ITERATIONS: usize = 16;
+/// Passes over every joint moving the ends back to where the joint wants them.
+const POSITION_ITERATIONS: usize = 4;
+
+/// One end of a joint, as the solver moves it.
+#[derive(Debug, Clone, Copy)]
+struct Body {
+    position: Vec2,
+    angle: f32,
+    velocity: Vec2,
+    angular_velocity: f32,
+    /// Zero for ends the joints can't move.
+    inverse_mass: f32,
+    inverse_inertia: f32,
+}
+impl Body {
+    fn fixed(position: Vec2, angle: f32) -> Self {
+        Self {
+            position,
+            angle,
+            velocity: Vec2::ZERO,
+            angular_velocity: 0.0,
+            inverse_mass: 0.0,
+            inverse_inertia: 0.0,
+        }
+    }
+    fn of(
+        transform: &Transform,
+        dynamic_object: Option<&DynamicObject>,
+        shape: Option<&Shape>,
+    ) -> Self {
+        let (position, angle) = (transform.translation.xy(), angle(transform));
+        match dynamic_object {
+            // Held still, as [`DynamicObject::is_sleeping`] describes.
+            Some(dynamic_object) if !dynamic_object.is_sleeping() => {
+                let inertia =
+                    shape.map_or(0.0, |shape| shape.moment_of_inertia(dynamic_object.mass));
+                Self {
+                    position,
+                    angle,
+                    velocity: dynamic_object.velocity,
+                    angular_velocity: dynamic_object.angular_velocity,
+                    inverse_mass: dynamic_object.inverse_mass(),
+                    inverse_inertia: if inertia > 0.0 { inertia.recip() } else { 0.0 },
+                }
+            }
+            _ => Self::fixed(position, angle),
+        }
+    }
+
+    /// World-space offset from the centre of the point at `local`.
+    fn arm(&self, local: Vec2) -> Vec2 {
+        Vec2::from_angle(self.angle).rotate(local)
+    }
+    fn velocity_at(&self, arm: Vec2) -> Vec2 {
+        self.velocity + self.angular_velocity * arm.perp()
+    }
+    /// How easily a push along `direction` at `arm` moves that point, the inverse of the mass
+    /// it feels.
+    fn give(&self, arm: Vec2, direction: Vec2) -> f32 {
+        self.inverse_mass + self.inverse_inertia * arm.perp_dot(direction).powi(2)
+    }
+    /// [`Body::give`] for a push in any direction.
+    fn give_matrix(&self, arm: Vec2) -> Mat2 {
+        Mat2::from_diagonal(Vec2::splat(self.inverse_mass))
+            + Mat2::from_cols(arm.perp() * -arm.y, arm.perp() * arm.x) * self.inverse_inertia
+    }
+    fn apply_impulse(&mut self, impulse: Vec2, arm: Vec2) {
+        self.velocity += impulse * self.inverse_mass;
+        self.angular_velocity += arm.perp_dot(impulse) * self.inverse_inertia;
+    }
+    /// Like [`Body::apply_impulse`], but moves the body instead of changing its velocity.
+    fn displace(&mut self, impulse: Vec2, arm: Vec2) {
+        self.position += impulse * self.inverse_mass;
+        self.angle += arm.perp_dot(impulse) * self.inverse_inertia;
+    }
+}
+
+fn angle(transform: &Transform) -> f32 {
+    transform.rotation.to_euler(EulerRot::ZYX).0
+}
+
+/// A [`RigidJoint`] between two of the solver's bodies.
+struct Link {
+    a: usize,
+    b: usize,
+    local_a: Vec2,
+    local_b: Vec2,
+    kind: RigidJointKind,
+    reference_angle: f32,
+}
+impl Link {
+    /// Which way the joint holds the points and how far they are out of place that way, with
+    /// the arms to use. Pins hold the points in every direction, so get `None`.
+    fn axis(&self, a: &Body, b: &Body) -> Option<(Vec2, f32, Vec2, Vec2)> {
+        let (arm_a, arm_b) = (a.arm(self.local_a), b.arm(self.local_b));
+        let apart = b.position + arm_b - a.position - arm_a;
+        match self.kind {
+            RigidJointKind::Distance(length) => Some((
+                apart.normalize_or_zero(),
+                apart.length() - length,
+                arm_a,
+                arm_b,
+            )),
+            // A slack rope holds nothing.
+            RigidJointKind::Rope(length) if apart.length() < length => None,
+            RigidJointKind::Rope(length) => Some((
+                apart.normalize_or_zero(),
+                apart.length() - length,
+                arm_a,
+                arm_b,
+            )),
+            RigidJointKind::Pin => None,
+            RigidJointKind::Prismatic(axis) => {
+                // Measured from the point of `a` under `b`'s attachment, which is where the
+                // slide has taken it.
+                let across = a.arm(axis).normalize_or_zero().perp();
+                Some((across, apart.dot(across), arm_a + apart, arm_b))
+            }
+        }
+    }
+
+    fn solve_velocity(&self, a: &mut Body, b: &mut Body) {
+        if let RigidJointKind::Prismatic(_) = self.kind {
+            let give = a.inverse_inertia + b.inverse_inertia;
+            if give > 0.0 {
+                let impulse = (a.angular_velocity - b.angular_velocity) / give;
+                a.angular_velocity -= impulse * a.inverse_inertia;
+                b.angular_velocity += impulse * b.inverse_inertia;
+            }
+        }
+        if let RigidJointKind::Pin = self.kind {
+            let (arm_a, arm_b) = (a.arm(self.local_a), b.arm(self.local_b));
+            let give = a.give_matrix(arm_a) + b.give_matrix(arm_b);
+            if give.determinant() != 0.0 {
+                let relative = b.velocity_at(arm_b) - a.velocity_at(arm_a);
+                let impulse = -(give.inverse() * relative);
+                a.apply_impulse(-impulse, arm_a);
+                b.apply_impulse(impulse, arm_b);
+            }
+            return;
+        }
+
+        let Some((direction, _, arm_a, arm_b)) = self.axis(a, b) else {
+            return;
+        };
+        let give = a.give(arm_a, direction) + b.give(arm_b, direction);
+        if give == 0.0 {
+            return;
+        }
+        let mut parting = (b.velocity_at(arm_b) - a.velocity_at(arm_a)).dot(direction);
+        if let RigidJointKind::Rope(_) = self.kind {
+            // A taut rope only stops the ends parting.
+            parting = parting.max(0.0);
+        }
+        let impulse = -direction * parting / give;
+        a.apply_impulse(-impulse, arm_a);
+        b.apply_impulse(impulse, arm_b);
+    }
+
+    fn solve_position(&self, a: &mut Body, b: &mut Body) {
+        if let RigidJointKind::Prismatic(_) = self.kind {
+            let give = a.inverse_inertia + b.inverse_inertia;
+            if give > 0.0 {
+                let error = b.angle - a.angle - self.reference_angle;
+                a.angle += error / give * a.inverse_inertia;
+                b.angle -= error / give * b.inverse_inertia;
+            }
+        }
+        if let RigidJointKind::Pin = self.kind {
+            let (arm_a, arm_b) = (a.arm(self.local_a), b.arm(self.local_b));
+            let give = a.give_matrix(arm_a) + b.give_matrix(arm_b);
+            if give.determinant() != 0.0 {
+                let apart = b.position + arm_b - a.position - arm_a;
+                let impulse = -(give.inverse() * apart);
+                a.displace(-impulse, arm_a);
+                b.displace(impulse, arm_b);
+            }
+            return;
+        }
+
+        let Some((direction, error, arm_a, arm_b)) = self.axis(a, b) else {
+            return;
+        };
+        let give = a.give(arm_a, direction) + b.give(arm_b, direction);
+        if give == 0.0 {
+            return;
+        }
+        let impulse = -direction * error / give;
+        a.displace(-impulse, arm_a);
+        b.displace(impulse, arm_b);
+    }
+}
+
+type Objects<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Transform,
+        Option<&'static mut DynamicObject>,
+        Option<&'static Shape>,
+    ),
+>;
+
+/// Notes how prismatic joints' ends are turned relative to each other before their first step
+/// moves them.
+pub(crate) fn record_reference_angles(
+    mut joints: Query<&mut RigidJoint>,
+    transforms: Query<&Transform>,
+) {
+    for mut joint in &mut joints {
+        if !matches!(joint.kind, RigidJointKind::Prismatic(_)) || joint.reference_angle.is_some() {
+            continue;
+        }
+        let turned = |anchor| match anchor {
+            JointAnchor::Entity(entity) => transforms.get(entity).ok().map(angle),
+            JointAnchor::Point(_) => Some(0.0),
+        };
+        if let (Some(a), Some(b)) = (turned(joint.a), turned(joint.b)) {
+            joint.reference_angle = Some(b - a);
+        }
+    }
+}
+
+/// Wakes the sleeping end of any joint whose other end is moving.
+fn wake_joined(joints: &Query<&RigidJoint>, objects: &mut Objects) {
+    for joint in joints {
+        let moving = |anchor| match anchor {
+            JointAnchor::Entity(entity) => {
+                objects.get(entity).is_ok_and(|(_, dynamic_object, _)| {
+                    dynamic_object.is_some_and(|dynamic_object| {
+                        !dynamic_object.is_sleeping() && sleep::is_moving(dynamic_object)
+                    })
+                })
+            }
+            JointAnchor::Point(_) => false,
+        };
+        let (a_moving, b_moving) = (moving(joint.a), moving(joint.b));
+        for (anchor, other_moving) in [(joint.a, b_moving), (joint.b, a_moving)] {
+            if let JointAnchor::Entity(entity) = anchor
+                && other_moving
+                && let Ok((_, Some(mut dynamic_object), _)) = objects.get_mut(entity)
+                && dynamic_object.is_sleeping()
+            {
+                dynamic_object.wake_up();
+            }
+        }
+    }
+}
+
+/// Enforces every [`RigidJoint`] by sequential impulses on velocity, then by moving the ends
+/// back into place.
+pub(crate) fn solve_joints(joints: Query<&RigidJoint>, mut objects: Objects) {
+    wake_joined(&joints, &mut objects);
+
+    let mut bodies = Vec::new();
+    let mut indices: HashMap<Entity, usize> = HashMap::default();
+    let mut links = Vec::new();
+    for joint in &joints {
+        let mut end = |anchor| match anchor {
+            JointAnchor::Point(point) => {
+                bodies.push(Body::fixed(point, 0.0));
+                Some(bodies.len() - 1)
+            }
+            JointAnchor::Entity(entity) => {
+                if let Some(&index) = indices.get(&entity) {
+                    return Some(index);
+                }
+                let (transform, dynamic_object, shape) = objects.get(entity).ok()?;
+                bodies.push(Body::of(transform, dynamic_object, shape));
+                indices.insert(entity, bodies.len() - 1);
+                Some(bodies.len() - 1)
+            }
+        };
+        let (Some(a), Some(b)) = (end(joint.a), end(joint.b)) else {
+            continue;
+        };
+        if a == b {
+            continue;
+        }
+        links.push(Link {
+            a,
+            b,
+            local_a: joint.local_a,
+            local_b: joint.local_b,
+            kind: joint.kind,
+            reference_angle: joint
+                .reference_angle
+                .unwrap_or(bodies[b].angle - bodies[a].angle),
+        });
+    }
+
+    let mut solve = |solve_link: fn(&Link, &mut Body, &mut Body)| {
+        for link in &links {
+            let (mut a, mut b) = (bodies[link.a], bodies[link.b]);
+            solve_link(link, &mut a, &mut b);
+            (bodies[link.a], bodies[link.b]) = (a, b);
+        }
+    };
+    for _ in 0..VELOCITY_ITERATIONS {
+        solve(Link::solve_velocity);
+    }
+    for _ in 0..POSITION_ITERATIONS {
+        solve(Link::solve_position);
+    }
+
+    for (entity, index) in indices {
+        let body = &bodies[index];
+        if body.inverse_mass == 0.0 && body.inverse_inertia == 0.0 {
+            continue;
+        }
+        let Ok((mut transform, Some(mut dynamic_object), _)) = objects.get_mut(entity) else {
+            continue;
+        };
+        let turned = body.angle - angle(&transform);
+        transform.translation = body.position.extend(transform.translation.z);
+        transform.rotate_z(turned);
+        dynamic_object.velocity = body.velocity;
+        dynamic_object.angular_velocity = body.angular_velocity;
+    }
+}
@@ -0,0 +1,899 @@
+use std::f32::consts::TAU;
+
+use bevy::{math::bounding::Aabb2d, prelude::*};
+
+use crate::components::Shape;
+
+/// How two overlapping shapes touch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContactManifold {
+    /// Unit vector pointing from the first shape towards the second. Moving the second shape
+    /// along it by `depth` separates them.
+    pub normal: Vec2,
+    /// How far the shapes overlap along `normal`.
+    pub depth: f32,
+    points: [Vec2; 2],
+    point_count: usize,
+}
+impl ContactManifold {
+    fn new(normal: Vec2, depth: f32, points: &[Vec2]) -> Self {
+        let mut manifold = Self {
+            normal,
+            depth,
+            points: [Vec2::ZERO; 2],
+            point_count: points.len(),
+        };
+        manifold.points[..points.len()].copy_from_slice(points);
+        manifold
+    }
+
+    /// One or two world-space points where the shapes touch.
+    pub fn points(&self) -> &[Vec2] {
+        &self.points[..self.point_count]
+    }
+
+    /// The same contact seen from the second shape.
+    pub fn flipped(mut self) -> Self {
+        self.normal = -self.normal;
+        self
+    }
+}
+
+/// Where a [`Shape`] on an entity with this transform is, and how it is turned.
+pub(crate) fn isometry(transform: &Transform) -> Isometry2d {
+    Isometry2d::new(
+        transform.translation.xy(),
+        Rot2::radians(transform.rotation.to_euler(EulerRot::ZYX).0),
+    )
+}
+
+impl Shape {
+    /// Describes how this shape touches `other`, or `None` if they don't overlap. Every shape
+    /// but a circle is turned by the rotation of its isometry.
+    pub fn contact(
+        &self,
+        isometry: impl Into<Isometry2d>,
+        other: &Self,
+        other_isometry: impl Into<Isometry2d>,
+    ) -> Option<ContactManifold> {
+        let (isometry, other_isometry) = (isometry.into(), other_isometry.into());
+        match (self, other) {
+            (Shape::Circle(radius), Shape::Circle(other_radius)) => circle_circle(
+                isometry.translation,
+                *radius,
+                other_isometry.translation,
+                *other_radius,
+            ),
+            (Shape::Circle(radius), Shape::Rect(width, height)) => circle_rect(
+                isometry.translation,
+                *radius,
+                other_isometry,
+                Vec2::new(width / 2.0, height / 2.0),
+            ),
+            (Shape::Rect(width, height), Shape::Circle(radius)) => circle_rect(
+                other_isometry.translation,
+                *radius,
+                isometry,
+                Vec2::new(width / 2.0, height / 2.0),
+            )
+            .map(ContactManifold::flipped),
+            _ => hull_hull(&self.hull(isometry), &other.hull(other_isometry)),
+        }
+    }
+
+    /// Axis-aligned box around the shape at `isometry`.
+    pub fn aabb(&self, isometry: impl Into<Isometry2d>) -> Aabb2d {
+        let hull = self.hull(isometry.into());
+        let (min, max) = hull.vertices.iter().fold(
+            (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY)),
+            |(min, max), vertex| (min.min(*vertex), max.max(*vertex)),
+        );
+        Aabb2d {
+            min: min - hull.radius,
+            max: max + hull.radius,
+        }
+    }
+
+    /// How far along the way from `from` to `to`, between 0 and 1, a circle of `radius` first
+    /// touches this shape, or `None` if it never does or already touches at `from`.
+    pub fn cast_circle(
+        &self,
+        isometry: impl Into<Isometry2d>,
+        radius: f32,
+        from: Vec2,
+        to: Vec2,
+    ) -> Option<f32> {
+        // Casting the circle's centre against the shape grown by the circle's radius.
+        let hull = self.hull(isometry.into());
+        let reach = hull.radius + radius;
+        let delta = to - from;
+
+        let faces = hull.faces().filter_map(|face| {
+            let approach = -delta.dot(face.outward);
+            let distance = (from - face.start).dot(face.outward) - reach;
+            if approach <= 0.0 || distance < 0.0 || distance > approach {
+                return None;
+            }
+            let fraction = distance / approach;
+            let hit = from + delta * fraction;
+            let along = face.end - face.start;
+            (0.0..=along.length_squared())
+                .contains(&(hit - face.start).dot(along))
+                .then_some(fraction)
+        });
+        let corners = hull.vertices.iter().filter_map(|vertex| {
+            let offset = from - *vertex;
+            let (a, b, c) = (
+                delta.length_squared(),
+                2.0 * offset.dot(delta),
+                offset.length_squared() - reach * reach,
+            );
+            let discriminant = b * b - 4.0 * a * c;
+            if a == 0.0 || c < 0.0 || discriminant < 0.0 {
+                return None;
+            }
+            let fraction = (-b - discriminant.sqrt()) / (2.0 * a);
+            (0.0..=1.0).contains(&fraction).then_some(fraction)
+        });
+        faces.chain(corners).min_by(f32::total_cmp)
+    }
+
+    /// Width of the shape at `isometry` across `direction`, the width it sweeps moving that way.
+    pub fn width_across(&self, isometry: impl Into<Isometry2d>, direction: Vec2) -> f32 {
+        let (low, high) = self
+            .hull(isometry.into())
+            .project(direction.perp().normalize_or_zero());
+        high - low
+    }
+
+    /// Whether `point` is inside or on the edge of the shape at `isometry`.
+    pub fn contains_point(&self, isometry: impl Into<Isometry2d>, point: Vec2) -> bool {
+        self.contact(isometry, &Shape::Circle(0.0), point).is_some()
+    }
+
+    /// The shape at `isometry` as a counter-clockwise convex polygon, with its rounded parts
+    /// approximated by short edges.
+    pub(crate) fn outline(&self, isometry: impl Into<Isometry2d>) -> Vec<Vec2> {
+        self.hull(isometry.into()).outline()
+    }
+
+    fn hull(&self, isometry: Isometry2d) -> Hull {
+        let (vertices, radius) = match self {
+            Shape::Circle(radius) => (vec![Vec2::ZERO], *radius),
+            Shape::Rect(width, height) => {
+                let half = Vec2::new(width / 2.0, height / 2.0);
+                let corners = vec![
+                    -half,
+                    Vec2::new(half.x, -half.y),
+                    half,
+                    Vec2::new(-half.x, half.y),
+                ];
+                (corners, 0.0)
+            }
+            Shape::Polygon(vertices) => (vertices.clone(), 0.0),
+            Shape::Capsule(radius, length) => (
+                vec![Vec2::new(0.0, -length / 2.0), Vec2::new(0.0, length / 2.0)],
+                *radius,
+            ),
+            Shape::Segment(start, end) => (vec![*start, *end], 0.0),
+        };
+        Hull::new(
+            vertices
+                .into_iter()
+                .map(|vertex| isometry.transform_point(vertex))
+                .collect(),
+            radius,
+        )
+    }
+}
+
+fn circle_circle(a: Vec2, a_radius: f32, b: Vec2, b_radius: f32) -> Option<ContactManifold> {
+    let delta = b - a;
+    let distance = delta.length();
+    let depth = a_radius + b_radius - distance;
+    if depth < 0.0 {
+        return None;
+    }
+    let normal = delta.try_normalize().unwrap_or(Vec2::Y);
+    let point = a + normal * (a_radius - depth / 2.0);
+    Some(ContactManifold::new(normal, depth, &[point]))
+}
+
+fn circle_rect(
+    center: Vec2,
+    radius: f32,
+    rect: Isometry2d,
+    half_size: Vec2,
+) -> Option<ContactManifold> {
+    // Works in the rectangle's frame, where it is axis-aligned around the origin.
+    let local = rect.inverse_transform_point(center);
+    let clamped = local.clamp(-half_size, half_size);
+    let (normal, depth, point) = if clamped != local {
+        // The centre is outside, so the closest point on the rectangle is on its boundary.
+        let delta = clamped - local;
+        let distance = delta.length();
+        if distance > radius {
+            return None;
+        }
+        (delta / distance, radius - distance, clamped)
+    } else {
+        // The centre is inside, so push the circle out through the nearest face.
+        let to_face = half_size - local.abs();
+        let outward = if to_face.x < to_face.y {
+            Vec2::new(local.x.signum(), 0.0)
+        } else {
+            Vec2::new(0.0, local.y.signum())
+        };
+        let face_distance = to_face.x.min(to_face.y);
+        (
+            -outward,
+            radius + face_distance,
+            local + outward * face_distance,
+        )
+    };
+    Some(ContactManifold::new(
+        rect.rotation * normal,
+        depth,
+        &[rect.transform_point(point)],
+    ))
+}
+
+/// A shape as a convex core grown by `radius`, with the core's vertices counter-clockwise in
+/// world space. Circles have a single vertex, and segments and capsules two.
+struct Hull {
+    vertices: Vec<Vec2>,
+    radius: f32,
+}
+impl Hull {
+    fn new(mut vertices: Vec<Vec2>, radius: f32) -> Self {
+        let doubled_area: f32 = (0..vertices.len())
+            .map(|i| vertices[i].perp_dot(vertices[(i + 1) % vertices.len()]))
+            .sum();
+        if doubled_area < 0.0 {
+            vertices.reverse();
+        }
+        Self { vertices, radius }
+    }
+
+    /// Each edge of the core as its start, end and outward normal. A segment has a face on
+    /// either side.
+    fn faces(&self) -> impl Iterator<Item = Face> + '_ {
+        let count = self.vertices.len();
+        (0..count).filter_map(move |i| {
+            let (start, end) = (self.vertices[i], self.vertices[(i + 1) % count]);
+            let outward = -(end - start).perp().try_normalize()?;
+            Some(Face {
+                start,
+                end,
+                outward,
+            })
+        })
+    }
+
+    /// The core's edges, or its only vertex as a zero-length edge.
+    fn edges(&self) -> Vec<[Vec2; 2]> {
+        match self.vertices.as_slice() {
+            [vertex] => vec![[*vertex; 2]],
+            _ => self.faces().map(|face| [face.start, face.end]).collect(),
+        }
+    }
+
+    /// Lowest and highest position along `axis`, including the radius.
+    fn project(&self, axis: Vec2) -> (f32, f32) {
+        let (low, high) = self
+            .vertices
+            .iter()
+            .map(|vertex| vertex.dot(axis))
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(low, high), along| {
+                (low.min(along), high.max(along))
+            });
+        (low - self.radius, high + self.radius)
+    }
+
+    /// The core vertex furthest along `direction`.
+    fn support(&self, direction: Vec2) -> Vec2 {
+        self.vertices
+            .iter()
+            .copied()
+            .max_by(|p, q| p.dot(direction).total_cmp(&q.dot(direction)))
+            .unwrap_or_default()
+    }
+
+    /// The core's vertices with an arc of the radius around each, between the normals of the
+    /// edges either side.
+    fn outline(&self) -> Vec<Vec2> {
+        if self.radius == 0.0 {
+            return self.vertices.clone();
+        }
+        let count = self.vertices.len();
+        let mut outline = Vec::new();
+        for (i, vertex) in self.vertices.iter().enumerate() {
+            let (from, turn) = if count == 1 {
+                (0.0, TAU)
+            } else {
+                let (previous, next) = (
+                    self.vertices[(i + count - 1) % count],
+                    self.vertices[(i + 1) % count],
+                );
+                let arriving = (previous - *vertex).perp();
+                let leaving = (*vertex - next).perp();
+                (
+                    arriving.to_angle(),
+                    arriving.angle_to(leaving).rem_euclid(TAU),
+                )
+            };
+            let steps = (turn / ARC_STEP).ceil().max(1.0) as usize;
+            // A full circle would repeat its first point.
+            let last = if count == 1 { steps - 1 } else { steps };
+            outline.extend((0..=last).map(|step| {
+                *vertex + Vec2::from_angle(from + turn * step as f32 / steps as f32) * self.radius
+            }));
+        }
+        outline
+    }
+
+    fn contains(&self, point: Vec2) -> bool {
+        self.vertices.len() >= 3
+            && self
+                .faces()
+                .all(|face| (point - face.start).dot(face.outward) <= 0.0)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Face {
+    start: Vec2,
+    end: Vec2,
+    outward: Vec2,
+}
+
+/// The face contact points are clipped against, on the first shape or the second.
+#[derive(Clone, Copy)]
+struct Reference {
+    face: Face,
+    of_a: bool,
+}
+
+/// Largest angle in radians between the points of an [`Hull::outline`] around a rounded corner.
+const ARC_STEP: f32 = TAU / 32.0;
+
+/// How much shallower the second shape's axis has to be to become the reference face.
+const REFERENCE_BIAS: f32 = 1e-3;
+/// How close to the contact normal a face's normal has to be to count as facing along it.
+const FACING: f32 = 1e-3;
+
+fn hull_hull(a: &Hull, b: &Hull) -> Option<ContactManifold> {
+    let (normal, depth, reference, fallback) = match closest_points(a, b) {
+        // Apart cores touch only through their radii, along the line between the closest points.
+        Some((on_a, on_b)) if on_a.distance(on_b) > 1e-4 => {
+            let distance = on_a.distance(on_b);
+            let depth = a.radius + b.radius - distance;
+            if depth < 0.0 {
+                return None;
+            }
+            let normal = (on_b - on_a) / distance;
+            let facing = |hull: &Hull, direction: Vec2| {
+                hull.faces()
+                    .find(|face| face.outward.dot(direction) > 1.0 - FACING)
+            };
+            let reference = facing(a, normal)
+                .map(|face| Reference { face, of_a: true })
+                .or_else(|| facing(b, -normal).map(|face| Reference { face, of_a: false }));
+            (normal, depth, reference, on_b - normal * b.radius)
+        }
+        // Overlapping cores separate along the axis of least overlap, either a face normal of
+        // one of them or the direction of a segment's core.
+        _ => {
+            let mut best: Option<(f32, Vec2, Option<Reference>)> = None;
+            let mut consider = |depth: f32, normal: Vec2, reference, bias: f32| {
+                if best.is_none_or(|(least, ..)| depth < least - bias) {
+                    best = Some((depth, normal, reference));
+                }
+            };
+            for face in a.faces() {
+                let depth = a.project(face.outward).1 - b.project(face.outward).0;
+                consider(
+                    depth,
+                    face.outward,
+                    Some(Reference { face, of_a: true }),
+                    0.0,
+                );
+            }
+            // Favours a's faces when b's are only about as shallow, so resting contacts between
+            // nearly parallel faces keep the same reference face from step to step.
+            for face in b.faces() {
+                let depth = b.project(face.outward).1 - a.project(face.outward).0;
+                consider(
+                    depth,
+                    -face.outward,
+                    Some(Reference { face, of_a: false }),
+                    REFERENCE_BIAS,
+                );
+            }
+            for hull in [a, b] {
+                if let [start, end] = hull.vertices.as_slice() {
+                    let along = (*end - *start).normalize_or_zero();
+                    for axis in [along, -along] {
+                        let depth = a.project(axis).1 - b.project(axis).0;
+                        consider(depth, axis, None, REFERENCE_BIAS);
+                    }
+                }
+            }
+            let (depth, normal, reference) = best.unwrap_or((a.radius + b.radius, Vec2::Y, None));
+            if depth < 0.0 {
+                return None;
+            }
+            let deepest = b.support(-normal) - normal * b.radius;
+            (normal, depth, reference, deepest)
+        }
+    };
+
+    let Some(Reference { face, of_a }) = reference else {
+        return Some(ContactManifold::new(normal, depth, &[fallback]));
+    };
+    let (reference, incident) = if of_a { (a, b) } else { (b, a) };
+    let outward = face.outward;
+    // The incident face is the one on the other shape facing the reference face the most.
+    let edge = incident
+        .faces()
+        .min_by(|p, q| p.outward.dot(outward).total_cmp(&q.outward.dot(outward)))
+        .map_or([incident.support(-outward); 2], |face| {
+            [face.start, face.end]
+        });
+
+    // Contact points are the part of the incident face within the reference face's sides and
+    // behind its surface, moved onto the incident shape's surface.
+    let along = (face.end - face.start).normalize();
+    let (start, end) = (face.start.dot(along), face.end.dot(along));
+    let clipped = clip(edge, along, start.min(end), start.max(end)).unwrap_or(edge);
+    let surface = face.start.dot(outward) + reference.radius;
+    let on_surface = clipped.map(|point| point - outward * incident.radius);
+    let mut points: Vec<Vec2> = on_surface
+        .into_iter()
+        .filter(|point| point.dot(outward) <= surface)
+        .collect();
+    if points.is_empty() {
+        points.extend(
+            on_surface
+                .into_iter()
+                .min_by(|p, q| p.dot(outward).total_cmp(&q.dot(outward))),
+        );
+    }
+    let across = -outward.perp();
+    points.sort_by(|p, q| p.dot(across).total_cmp(&q.dot(across)));
+    points.dedup_by(|p, q| p.distance(*q) < 1e-3);
+    Some(ContactManifold::new(normal, depth, &points))
+}
+
+/// The closest pair of points on the two cores, or `None` if the cores overlap.
+fn closest_points(a: &Hull, b: &Hull) -> Option<(Vec2, Vec2)> {
+    let (a_edges, b_edges) = (a.edges(), b.edges());
+    let crossing = a_edges
+        .iter()
+        .any(|p| b_edges.iter().any(|q| segments_cross(*p, *q)));
+    if crossing
+        || a.vertices.iter().any(|vertex| b.contains(*vertex))
+        || b.vertices.iter().any(|vertex| a.contains(*vertex))
+    {
+        return None;
+    }
+    let from_a = a.vertices.iter().flat_map(|vertex| {
+        b_edges
+            .iter()
+            .map(|edge| (*vertex, closest_on_segment(*vertex, *edge)))
+    });
+    let from_b = b.vertices.iter().flat_map(|vertex| {
+        a_edges
+            .iter()
+            .map(|edge| (closest_on_segment(*vertex, *edge), *vertex))
+    });
+    from_a
+        .chain(from_b)
+        .min_by(|(p, q), (r, s)| p.distance_squared(*q).total_cmp(&r.distance_squared(*s)))
+}
+
+fn closest_on_segment(point: Vec2, [start, end]: [Vec2; 2]) -> Vec2 {
+    let delta = end - start;
+    let length_squared = delta.length_squared();
+    if length_squared <= 0.0 {
+        return start;
+    }
+    start + delta * ((point - start).dot(delta) / length_squared).clamp(0.0, 1.0)
+}
+
+/// Whether two segments cross at a point inside both.
+fn segments_cross([p, q]: [Vec2; 2], [r, s]: [Vec2; 2]) -> bool {
+    let side = |from: Vec2, to: Vec2, point: Vec2| (to - from).perp_dot(point - from);
+    side(p, q, r) * side(p, q, s) < 0.0 && side(r, s, p) * side(r, s, q) < 0.0
+}
+
+/// The part of `edge` whose position along `axis` is between `low` and `high`.
+fn clip(edge: [Vec2; 2], axis: Vec2, low: f32, high: f32) -> Option<[Vec2; 2]> {
+    let (start, end) = (edge[0].dot(axis), edge[1].dot(axis));
+    let change = end - start;
+    if change.abs() <= f32::EPSILON {
+        return (low..=high).contains(&start).then_some(edge);
+    }
+    let (t_low, t_high) = ((low - start) / change, (high - start) / change);
+    let (from, to) = (t_low.min(t_high).max(0.0), t_low.max(t_high).min(1.0));
+    (from <= to).then(|| [edge[0].lerp(edge[1], from), edge[0].lerp(edge[1], to)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: Vec2, b: Vec2) {
+        assert!(a.distance(b) < 1e-4, "{a} != {b}");
+    }
+
+    #[test]
+    fn separate_shapes_have_no_contact() {
+        let circle = Shape::Circle(10.0);
+        let rect = Shape::Rect(20.0, 20.0);
+        let far = Vec2::new(100.0, 0.0);
+        assert_eq!(circle.contact(Vec2::ZERO, &circle, far), None);
+        assert_eq!(circle.contact(Vec2::ZERO, &rect, far), None);
+        assert_eq!(rect.contact(Vec2::ZERO, &circle, far), None);
+        assert_eq!(rect.contact(Vec2::ZERO, &rect, far), None);
+        // Close on each axis but apart diagonally.
+        assert_eq!(
+            circle.contact(Vec2::ZERO, &rect, Vec2::new(19.0, 19.0)),
+            None
+        );
+    }
+
+    #[test]
+    fn circle_circle() {
+        let manifold = Shape::Circle(10.0)
+            .contact(Vec2::ZERO, &Shape::Circle(5.0), Vec2::new(0.0, 12.0))
+            .unwrap();
+        assert_close(manifold.normal, Vec2::Y);
+        assert!((manifold.depth - 3.0).abs() < 1e-4);
+        assert_eq!(manifold.points().len(), 1);
+        assert_close(manifold.points()[0], Vec2::new(0.0, 8.5));
+    }
+
+    #[test]
+    fn circle_circle_on_the_same_spot() {
+        let manifold = Shape::Circle(10.0)
+            .contact(Vec2::ZERO, &Shape::Circle(10.0), Vec2::ZERO)
+            .unwrap();
+        assert!((manifold.normal.length() - 1.0).abs() < 1e-4);
+        assert!((manifold.depth - 20.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn circle_rect_from_outside() {
+        // A ball sinking 5 units into the top of a floor.
+        let manifold = Shape::Circle(50.0)
+            .contact(
+                Vec2::new(30.0, -430.0),
+                &Shape::Rect(10000.0, 50.0),
+                Vec2::new(0.0, -500.0),
+            )
+            .unwrap();
+        assert_close(manifold.normal, -Vec2::Y);
+        assert!((manifold.depth - 5.0).abs() < 1e-4);
+        assert_eq!(manifold.points().len(), 1);
+        assert_close(manifold.points()[0], Vec2::new(30.0, -475.0));
+    }
+
+    #[test]
+    fn circle_rect_at_a_corner() {
+        let manifold = Shape::Circle(10.0)
+            .contact(Vec2::new(16.0, 16.0), &Shape::Rect(20.0, 20.0), Vec2::ZERO)
+            .unwrap();
+        assert_close(manifold.normal, -Vec2::ONE.normalize());
+        assert!((manifold.depth - (10.0 - 6.0 * 2.0_f32.sqrt())).abs() < 1e-4);
+        assert_close(manifold.points()[0], Vec2::new(10.0, 10.0));
+    }
+
+    #[test]
+    fn circle_rect_with_centre_inside() {
+        let manifold = Shape::Circle(5.0)
+            .contact(Vec2::new(8.0, 0.0), &Shape::Rect(20.0, 40.0), Vec2::ZERO)
+            .unwrap();
+        // Closest to the right face, so the circle leaves to the right.
+        assert_close(manifold.normal, -Vec2::X);
+        assert!((manifold.depth - 7.0).abs() < 1e-4);
+        assert_close(manifold.points()[0], Vec2::new(10.0, 0.0));
+    }
+
+    #[test]
+    fn rect_circle_is_circle_rect_flipped() {
+        let circle = Shape::Circle(50.0);
+        let rect = Shape::Rect(200.0, 50.0);
+        let (circle_position, rect_position) = (Vec2::new(10.0, 60.0), Vec2::ZERO);
+        let manifold = rect
+            .contact(rect_position, &circle, circle_position)
+            .unwrap();
+        let reverse = circle
+            .contact(circle_position, &rect, rect_position)
+            .unwrap();
+        assert_close(manifold.normal, -reverse.normal);
+        assert_close(manifold.normal, Vec2::Y);
+        assert_eq!(manifold.depth, reverse.depth);
+        assert_eq!(manifold.points(), reverse.points());
+    }
+
+    #[test]
+    fn rect_rect_resting_face_to_face() {
+        // A box sitting 2 units into a wider floor, offset to one side.
+        let manifold = Shape::Rect(10000.0, 50.0)
+            .contact(
+                Vec2::new(0.0, -500.0),
+                &Shape::Rect(40.0, 20.0),
+                Vec2::new(100.0, -467.0),
+            )
+            .unwrap();
+        assert_close(manifold.normal, Vec2::Y);
+        assert!((manifold.depth - 2.0).abs() < 1e-4);
+        assert_eq!(manifold.points().len(), 2);
+        assert_close(manifold.points()[0], Vec2::new(80.0, -477.0));
+        assert_close(manifold.points()[1], Vec2::new(120.0, -477.0));
+    }
+
+    #[test]
+    fn rect_rect_picks_the_shallowest_axis() {
+        let manifold = Shape::Rect(20.0, 20.0)
+            .contact(Vec2::ZERO, &Shape::Rect(20.0, 20.0), Vec2::new(-18.0, 5.0))
+            .unwrap();
+        assert_close(manifold.normal, -Vec2::X);
+        assert!((manifold.depth - 2.0).abs() < 1e-4);
+        assert_eq!(manifold.points().len(), 2);
+        assert_close(manifold.points()[0], Vec2::new(-8.0, -5.0));
+        assert_close(manifold.points()[1], Vec2::new(-8.0, 10.0));
+    }
+
+    #[test]
+    fn rect_rect_touching_at_a_corner() {
+        let manifold = Shape::Rect(20.0, 20.0)
+            .contact(Vec2::ZERO, &Shape::Rect(20.0, 20.0), Vec2::new(20.0, 20.0))
+            .unwrap();
+        assert_eq!(manifold.depth, 0.0);
+        assert_eq!(manifold.points().len(), 1);
+        assert_close(manifold.points()[0], Vec2::new(10.0, 10.0));
+    }
+
+    fn turned(position: Vec2, angle: f32) -> Isometry2d {
+        Isometry2d::new(position, Rot2::radians(angle))
+    }
+
+    #[test]
+    fn turned_rects_apart_where_their_bounding_boxes_overlap() {
+        let diamond = turned(Vec2::ZERO, std::f32::consts::FRAC_PI_4);
+        let square = Shape::Rect(20.0, 20.0);
+        assert_eq!(
+            square.contact(diamond, &square, Vec2::new(18.0, 18.0)),
+            None
+        );
+        assert!(!square.intersects(diamond, &square, Vec2::new(18.0, 18.0)));
+        assert!(square.intersects(diamond, &square, Vec2::new(16.0, 16.0)));
+    }
+
+    #[test]
+    fn turned_rect_standing_on_a_corner() {
+        // A diamond whose bottom corner is 1 unit into the floor.
+        let half_diagonal = 10.0 * 2.0_f32.sqrt();
+        let manifold = Shape::Rect(100.0, 20.0)
+            .contact(
+                Vec2::new(0.0, -10.0),
+                &Shape::Rect(20.0, 20.0),
+                turned(
+                    Vec2::new(0.0, half_diagonal - 1.0),
+                    std::f32::consts::FRAC_PI_4,
+                ),
+            )
+            .unwrap();
+        assert_close(manifold.normal, Vec2::Y);
+        assert!((manifold.depth - 1.0).abs() < 1e-4);
+        assert_eq!(manifold.points().len(), 1);
+        assert_close(manifold.points()[0], Vec2::new(0.0, -1.0));
+    }
+
+    #[test]
+    fn turned_rect_resting_on_a_ramp() {
+        // A box 2 units into a ramp tilted the same way, between 20 and 40 units along it.
+        let ramp = turned(Vec2::ZERO, 0.5);
+        let manifold = Shape::Rect(200.0, 20.0)
+            .contact(
+                ramp,
+                &Shape::Rect(20.0, 10.0),
+                turned(ramp.transform_point(Vec2::new(30.0, 13.0)), 0.5),
+            )
+            .unwrap();
+        assert_close(manifold.normal, ramp.rotation * Vec2::Y);
+        assert!((manifold.depth - 2.0).abs() < 1e-4);
+        assert_eq!(manifold.points().len(), 2);
+        assert_close(
+            manifold.points()[0],
+            ramp.transform_point(Vec2::new(20.0, 8.0)),
+        );
+        assert_close(
+            manifold.points()[1],
+            ramp.transform_point(Vec2::new(40.0, 8.0)),
+        );
+    }
+
+    #[test]
+    fn circle_on_a_turned_rect() {
+        let ramp = turned(Vec2::ZERO, -0.3);
+        let manifold = Shape::Circle(10.0)
+            .contact(
+                ramp.transform_point(Vec2::new(50.0, 17.0)),
+                &Shape::Rect(200.0, 20.0),
+                ramp,
+            )
+            .unwrap();
+        assert_close(manifold.normal, ramp.rotation * -Vec2::Y);
+        assert!((manifold.depth - 3.0).abs() < 1e-4);
+        assert_close(
+            manifold.points()[0],
+            ramp.transform_point(Vec2::new(50.0, 10.0)),
+        );
+    }
+
+    #[test]
+    fn capsule_lying_on_a_rect() {
+        let manifold = Shape::Rect(200.0, 20.0)
+            .contact(
+                Vec2::new(0.0, -10.0),
+                &Shape::Capsule(5.0, 40.0),
+                turned(Vec2::new(0.0, 4.0), std::f32::consts::FRAC_PI_2),
+            )
+            .unwrap();
+        assert_close(manifold.normal, Vec2::Y);
+        assert!((manifold.depth - 1.0).abs() < 1e-4);
+        assert_eq!(manifold.points().len(), 2);
+        assert_close(manifold.points()[0], Vec2::new(-20.0, -1.0));
+        assert_close(manifold.points()[1], Vec2::new(20.0, -1.0));
+    }
+
+    #[test]
+    fn polygon_winding_does_not_matter() {
+        let wedge = [
+            Vec2::new(-20.0, -10.0),
+            Vec2::new(20.0, -10.0),
+            Vec2::new(0.0, 20.0),
+        ];
+        let floor = Shape::Rect(200.0, 20.0);
+        for vertices in [wedge.to_vec(), wedge.iter().rev().copied().collect()] {
+            let manifold = floor
+                .contact(
+                    Vec2::new(0.0, -10.0),
+                    &Shape::Polygon(vertices),
+                    Vec2::new(0.0, 9.0),
+                )
+                .unwrap();
+            assert_close(manifold.normal, Vec2::Y);
+            assert!((manifold.depth - 1.0).abs() < 1e-4);
+            assert_eq!(manifold.points().len(), 2);
+            assert_close(manifold.points()[0], Vec2::new(-20.0, -1.0));
+            assert_close(manifold.points()[1], Vec2::new(20.0, -1.0));
+        }
+    }
+
+    #[test]
+    fn polygon_corner_into_a_turned_rect() {
+        let triangle = Shape::Polygon(vec![
+            Vec2::new(0.0, -10.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(-10.0, 10.0),
+        ]);
+        let wall = turned(Vec2::new(0.0, -20.0), 0.2);
+        let manifold = Shape::Rect(100.0, 20.0)
+            .contact(wall, &triangle, wall.transform_point(Vec2::new(0.0, 18.0)))
+            .unwrap();
+        // The triangle isn't turned with the wall, so only its tip reaches in.
+        assert_close(manifold.normal, wall.rotation * Vec2::Y);
+        assert_eq!(manifold.points().len(), 1);
+        let tip = wall.transform_point(Vec2::new(0.0, 18.0)) - Vec2::new(0.0, 10.0);
+        assert_close(manifold.points()[0], tip);
+        assert!((manifold.depth - (10.0 - wall.inverse_transform_point(tip).y)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn circle_resting_on_a_segment() {
+        let manifold = Shape::Segment(Vec2::new(-50.0, 0.0), Vec2::new(50.0, 0.0))
+            .contact(Vec2::ZERO, &Shape::Circle(10.0), Vec2::new(10.0, 8.0))
+            .unwrap();
+        assert_close(manifold.normal, Vec2::Y);
+        assert!((manifold.depth - 2.0).abs() < 1e-4);
+        assert_eq!(manifold.points().len(), 1);
+        assert_close(manifold.points()[0], Vec2::new(10.0, -2.0));
+    }
+
+    #[test]
+    fn circle_past_the_end_of_a_segment() {
+        let segment = Shape::Segment(Vec2::new(-50.0, 0.0), Vec2::new(50.0, 0.0));
+        let circle = Shape::Circle(10.0);
+        let manifold = segment
+            .contact(Vec2::ZERO, &circle, Vec2::new(56.0, 8.0))
+            .unwrap();
+        assert_close(manifold.normal, Vec2::new(0.6, 0.8));
+        assert!((manifold.depth - 0.0).abs() < 1e-4);
+        assert_close(manifold.points()[0], Vec2::new(50.0, 0.0));
+        assert_eq!(
+            segment.contact(Vec2::ZERO, &circle, Vec2::new(57.0, 8.0)),
+            None
+        );
+    }
+
+    #[test]
+    fn crossing_capsules() {
+        let manifold = Shape::Capsule(5.0, 40.0)
+            .contact(
+                Vec2::ZERO,
+                &Shape::Capsule(5.0, 40.0),
+                turned(Vec2::new(0.0, 15.0), std::f32::consts::FRAC_PI_2),
+            )
+            .unwrap();
+        // Pushed out the shorter way, past the top of the upright one.
+        assert_close(manifold.normal, Vec2::Y);
+        assert!((manifold.depth - 15.0).abs() < 1e-4);
+        assert_eq!(manifold.points().len(), 1);
+        assert_close(manifold.points()[0], Vec2::new(0.0, 25.0));
+    }
+
+    #[test]
+    fn circle_cast_onto_a_face() {
+        let fraction = Shape::Rect(100.0, 50.0)
+            .cast_circle(
+                Vec2::new(0.0, -25.0),
+                5.0,
+                Vec2::new(10.0, 105.0),
+                Vec2::new(10.0, -95.0),
+            )
+            .unwrap();
+        assert!((fraction - 0.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn circle_cast_onto_a_corner() {
+        let square = Shape::Rect(20.0, 20.0);
+        // Passing just inside the corner's reach, so only the rounded corner is hit.
+        let fraction = square
+            .cast_circle(
+                Vec2::ZERO,
+                5.0,
+                Vec2::new(-50.0, 13.0),
+                Vec2::new(50.0, 13.0),
+            )
+            .unwrap();
+        let hit = Vec2::new(-50.0, 13.0).lerp(Vec2::new(50.0, 13.0), fraction);
+        assert!((hit.distance(Vec2::new(-10.0, 10.0)) - 5.0).abs() < 1e-3);
+        assert_eq!(
+            square.cast_circle(
+                Vec2::ZERO,
+                5.0,
+                Vec2::new(-50.0, 16.0),
+                Vec2::new(50.0, 16.0)
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn circle_cast_stopping_short() {
+        let segment = Shape::Segment(Vec2::new(0.0, -50.0), Vec2::new(0.0, 50.0));
+        assert_eq!(
+            segment.cast_circle(
+                Vec2::ZERO,
+                5.0,
+                Vec2::new(-100.0, 0.0),
+                Vec2::new(-6.0, 0.0)
+            ),
+            None
+        );
+        let fraction = segment
+            .cast_circle(
+                Vec2::ZERO,
+                5.0,
+                Vec2::new(-100.0, 0.0),
+                Vec2::new(100.0, 0.0),
+            )
+            .unwrap();
+        assert!((fraction - 95.0 / 200.0).abs() < 1e-4);
+    }
+}
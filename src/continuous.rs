@@ -0,0 +1,72 @@
+use bevy::prelude::*;
+
+use crate::{
+    collision::{Solid, layers_interact},
+    components::{CollisionLayers, ContinuousCollision, DynamicObject, Shape},
+    contact::isometry,
+};
+
+/// How far past the time of impact a swept circle is left, so the next step's contact
+/// resolution sees it touching and bounces it.
+const SWEEP_OVERLAP: f32 = 0.1;
+
+pub(crate) fn start_sweeps(mut swept: Query<(&mut ContinuousCollision, &Transform)>) {
+    for (mut continuous_collision, transform) in &mut swept {
+        continuous_collision.start = transform.translation.xy();
+    }
+}
+
+type Swept<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static ContinuousCollision,
+        &'static mut Transform,
+        &'static Shape,
+        &'static DynamicObject,
+        Option<&'static CollisionLayers>,
+    ),
+>;
+
+type Obstacles<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Transform,
+        &'static Shape,
+        Option<&'static CollisionLayers>,
+    ),
+    (Without<DynamicObject>, Solid),
+>;
+
+/// Moves swept circles back to where their path this step first touched a static shape.
+pub(crate) fn sweep_circles(mut swept: Swept, obstacles: Obstacles) {
+    for (continuous_collision, mut transform, shape, dynamic_object, layers) in &mut swept {
+        let Shape::Circle(radius) = shape else {
+            continue;
+        };
+        let (from, to) = (continuous_collision.start, transform.translation.xy());
+        if dynamic_object.is_sleeping() || from == to {
+            continue;
+        }
+
+        let circle = Shape::Circle(*radius);
+        let impact = obstacles
+            .iter()
+            .filter(|(.., obstacle_layers)| layers_interact(layers, *obstacle_layers))
+            .filter_map(|(obstacle_transform, obstacle, _)| {
+                let isometry = isometry(obstacle_transform);
+                // Contacts already touching at the start are left to the collision step.
+                if circle.intersects(from, obstacle, isometry) {
+                    return None;
+                }
+                obstacle.cast_circle(isometry, *radius, from, to)
+            })
+            .min_by(f32::total_cmp);
+        if let Some(fraction) = impact {
+            let direction = (to - from).normalize();
+            let stop = from.lerp(to, fraction) + direction * SWEEP_OVERLAP;
+            transform.translation = stop.extend(transform.translation.z);
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use bevy::prelude::*;
+
+use crate::{
+    PhysicsConfig,
+    components::{Charge, DynamicObject, Field, ForceLabel, Shape},
+    contact::isometry,
+};
+
+/// Pushes the Coulomb force of every [`Charge`] on every charged [`DynamicObject`].
+pub(crate) fn push_charges(
+    charges: Query<(Entity, &Transform, &Charge)>,
+    mut dynamic_objects: Query<(Entity, &mut DynamicObject), With<Charge>>,
+    config: Res<PhysicsConfig>,
+) {
+    let sources: Vec<_> = charges
+        .iter()
+        .map(|(entity, transform, charge)| (entity, transform.translation.xy(), charge.0))
+        .collect();
+    for (entity, mut dynamic_object) in &mut dynamic_objects {
+        let Some(&(_, position, charge)) = sources.iter().find(|(source, ..)| *source == entity)
+        else {
+            continue;
+        };
+        let force = sources
+            .iter()
+            .filter(|(source, ..)| *source != entity)
+            .filter_map(|(_, other, other_charge)| {
+                // Charges on the same spot have no direction to push in.
+                let offset = (position - *other).try_normalize()?;
+                Some(
+                    offset * config.coulomb_constant * charge * other_charge
+                        / position.distance_squared(*other),
+                )
+            })
+            .sum();
+        dynamic_object.add_labeled_force(force, ForceLabel::Electric);
+    }
+}
+
+/// Pushes every charged [`DynamicObject`] inside a [`Field`] by the Lorentz force.
+pub(crate) fn apply_fields(
+    fields: Query<(&Field, &Shape, &Transform)>,
+    mut dynamic_objects: Query<(&mut DynamicObject, &Charge, &Transform)>,
+) {
+    for (field, shape, field_transform) in &fields {
+        let field_isometry = isometry(field_transform);
+        for (mut dynamic_object, charge, transform) in &mut dynamic_objects {
+            if !shape.contains_point(field_isometry, transform.translation.xy()) {
+                continue;
+            }
+            let electric = field.electric * charge.0;
+            // The velocity crossed with a field out of the screen.
+            let magnetic = -dynamic_object.velocity.perp() * field.magnetic * charge.0;
+            dynamic_object.add_labeled_force(electric, ForceLabel::Electric);
+            dynamic_object.add_labeled_force(magnetic, ForceLabel::Magnetic);
+        }
+    }
+}
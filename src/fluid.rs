@@ -0,0 +1,170 @@
+use bevy::{math::bounding::IntersectsVolume, prelude::*};
+
+use crate::{
+    Gravity, PhysicsConfig,
+    components::{Drag, DynamicObject, FluidRegion, ForceLabel, Shape},
+    contact::isometry,
+};
+
+/// Pushes air resistance against every [`DynamicObject`] with [`Drag`].
+pub(crate) fn apply_drag(
+    mut dynamic_objects: Query<(&mut DynamicObject, &Drag, &Transform, Option<&Shape>)>,
+    config: Res<PhysicsConfig>,
+) {
+    for (mut dynamic_object, drag, transform, shape) in &mut dynamic_objects {
+        let velocity = dynamic_object.velocity;
+        let speed = velocity.length();
+        if speed == 0.0 {
+            continue;
+        }
+        let width = shape.map_or(0.0, |shape| {
+            shape.width_across(isometry(transform), velocity)
+        });
+        let quadratic = 0.5 * config.air_density * drag.coefficient * width * speed;
+        dynamic_object.add_labeled_force(-velocity * (drag.linear + quadratic), ForceLabel::Drag);
+    }
+}
+
+/// Lifts every [`DynamicObject`] in a [`FluidRegion`] by the weight of the fluid it pushes
+/// aside, and slows it by the fluid's viscosity.
+pub(crate) fn apply_fluids(
+    regions: Query<(&FluidRegion, &Shape, &Transform)>,
+    mut dynamic_objects: Query<(&mut DynamicObject, &Shape, &Transform), Without<FluidRegion>>,
+    gravity: Res<Gravity>,
+) {
+    for (region, region_shape, region_transform) in &regions {
+        let region_isometry = isometry(region_transform);
+        let (bounds, outline) = (
+            region_shape.aabb(region_isometry),
+            region_shape.outline(region_isometry),
+        );
+        for (mut dynamic_object, shape, transform) in &mut dynamic_objects {
+            let object_isometry = isometry(transform);
+            if !shape.aabb(object_isometry).intersects(&bounds) {
+                continue;
+            }
+            let submerged = clip(shape.outline(object_isometry), &outline);
+            let Some(Area {
+                area,
+                centroid,
+                polar_moment,
+            }) = Area::of(&submerged)
+            else {
+                continue;
+            };
+
+            // Viscous drag on every bit of the submerged part adds up to a force at its centroid
+            // and a torque against the spin.
+            let velocity = dynamic_object.velocity_at_point(centroid, transform.translation.xy());
+            let spin = dynamic_object.angular_velocity;
+            dynamic_object.add_labeled_force_at_point(
+                -gravity.0 * region.density * area,
+                centroid,
+                ForceLabel::Buoyancy,
+            );
+            dynamic_object.add_labeled_force_at_point(
+                -velocity * region.viscosity * area,
+                centroid,
+                ForceLabel::Drag,
+            );
+            dynamic_object.add_torque(-spin * region.viscosity * polar_moment);
+        }
+    }
+}
+
+/// Size and balance point of a polygon.
+struct Area {
+    area: f32,
+    centroid: Vec2,
+    /// Second moment of area about the centroid, around the axis out of the plane.
+    polar_moment: f32,
+}
+impl Area {
+    /// Measures a counter-clockwise polygon, or `None` if it has no area.
+    fn of(polygon: &[Vec2]) -> Option<Self> {
+        // Sum over the triangles fanning out from the first vertex, kept close to keep the sums
+        // precise.
+        let origin = *polygon.first()?;
+        let (mut area, mut first_moment, mut second_moment) = (0.0, Vec2::ZERO, 0.0);
+        for (i, a) in polygon.iter().enumerate() {
+            let (a, b) = (*a - origin, polygon[(i + 1) % polygon.len()] - origin);
+            let doubled_area = a.perp_dot(b);
+            area += doubled_area / 2.0;
+            first_moment += (a + b) * doubled_area / 6.0;
+            second_moment += doubled_area * (a.dot(a) + a.dot(b) + b.dot(b)) / 12.0;
+        }
+        if area <= f32::EPSILON {
+            return None;
+        }
+        let centroid = first_moment / area;
+        Some(Self {
+            area,
+            centroid: origin + centroid,
+            polar_moment: second_moment - area * centroid.length_squared(),
+        })
+    }
+}
+
+/// The part of convex polygon `subject` inside the convex counter-clockwise polygon `clip`.
+fn clip(subject: Vec<Vec2>, clip: &[Vec2]) -> Vec<Vec2> {
+    let mut kept = subject;
+    for (i, start) in clip.iter().enumerate() {
+        let along = clip[(i + 1) % clip.len()] - *start;
+        // Positive on the inside, to the left of the edge.
+        let inside = |point: Vec2| along.perp_dot(point - *start);
+        let previous_kept = std::mem::take(&mut kept);
+        for (j, current) in previous_kept.iter().enumerate() {
+            let previous = previous_kept[(j + previous_kept.len() - 1) % previous_kept.len()];
+            let (from, to) = (inside(previous), inside(*current));
+            if (from >= 0.0) != (to >= 0.0) {
+                kept.push(previous.lerp(*current, from / (from - to)));
+            }
+            if to >= 0.0 {
+                kept.push(*current);
+            }
+        }
+    }
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(center: Vec2, half: f32) -> Vec<Vec2> {
+        [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+            .map(|(x, y)| center + Vec2::new(x, y) * half)
+            .to_vec()
+    }
+
+    #[test]
+    fn square_measures() {
+        let area = Area::of(&square(Vec2::new(3.0, 4.0), 1.0)).unwrap();
+        assert!((area.area - 4.0).abs() < 1e-5);
+        assert!(area.centroid.distance(Vec2::new(3.0, 4.0)) < 1e-5);
+        // A 2 by 2 square has (2⁴ + 2⁴) / 12 about its centre.
+        assert!((area.polar_moment - 32.0 / 12.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn clipping_keeps_the_overlap() {
+        let overlap = clip(square(Vec2::ZERO, 1.0), &square(Vec2::new(1.0, 1.5), 1.0));
+        let area = Area::of(&overlap).unwrap();
+        assert!((area.area - 0.5).abs() < 1e-5, "{}", area.area);
+        assert!(area.centroid.distance(Vec2::new(0.5, 0.75)) < 1e-5);
+    }
+
+    #[test]
+    fn clipping_apart_leaves_nothing() {
+        let overlap = clip(square(Vec2::ZERO, 1.0), &square(Vec2::new(5.0, 0.0), 1.0));
+        assert!(Area::of(&overlap).is_none());
+    }
+
+    #[test]
+    fn circle_outline_has_the_circle_area() {
+        let outline = Shape::Circle(10.0).outline(Vec2::new(5.0, 5.0));
+        let area = Area::of(&outline).unwrap();
+        assert!((area.area - std::f32::consts::PI * 100.0).abs() < 100.0 * 0.03);
+        assert!(area.centroid.distance(Vec2::new(5.0, 5.0)) < 1e-3);
+    }
+}
@@ -0,0 +1,35 @@
+use bevy::prelude::*;
+
+use crate::components::{DynamicObject, ForceField, ForceLabel};
+
+/// Pushes every [`ForceField`] onto the [`DynamicObject`]s in its range.
+pub(crate) fn apply_force_fields(
+    fields: Query<(&ForceField, &Transform)>,
+    mut dynamic_objects: Query<(&mut DynamicObject, &Transform)>,
+) {
+    for (field, field_transform) in &fields {
+        let center = field_transform.translation.xy();
+        for (mut dynamic_object, transform) in &mut dynamic_objects {
+            if let Some(force) = field.force_at(transform.translation.xy() - center) {
+                dynamic_object.add_labeled_force(force, ForceLabel::ForceField);
+            }
+        }
+    }
+}
+
+/// Counts down each [`ForceField::lifetime`] by a step, despawning the fields that run out.
+pub(crate) fn expire_force_fields(
+    mut fields: Query<(Entity, &mut ForceField)>,
+    time: Res<Time<Fixed>>,
+    mut commands: Commands,
+) {
+    for (entity, mut field) in &mut fields {
+        let Some(lifetime) = field.lifetime.as_mut() else {
+            continue;
+        };
+        *lifetime -= time.delta_secs();
+        if *lifetime <= 0.0 {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+use bevy::{app::FixedMain, prelude::*};
+
+use crate::{CollisionEnded, CollisionStarted, PhysicsPlugin};
+
+/// A windowless [`App`] running only [`MinimalPlugins`] and the [`PhysicsPlugin`].
+///
+/// Physics steps are driven manually with [`HeadlessSimulation::step`] instead of by the wall
+/// clock, so the same scene always produces the same result.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use physics_project::{PhysicsPlugin, HeadlessSimulation, components::*};
+/// let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+/// let ball = sim
+///     .world_mut()
+///     .spawn((Shape::Circle(50.0), DynamicObject::new(5.0), Transform::default()))
+///     .id();
+/// sim.step(10);
+/// assert!(sim.world().get::<Transform>(ball).unwrap().translation.y < 0.0);
+/// ```
+pub struct HeadlessSimulation {
+    app: App,
+    ready: bool,
+}
+impl HeadlessSimulation {
+    pub fn new(physics: PhysicsPlugin) -> Self {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, physics));
+        Self { app, ready: false }
+    }
+
+    pub fn app_mut(&mut self) -> &mut App {
+        &mut self.app
+    }
+    pub fn world(&self) -> &World {
+        self.app.world()
+    }
+    pub fn world_mut(&mut self) -> &mut World {
+        self.app.world_mut()
+    }
+
+    /// Length of one step, as set by [`PhysicsPlugin::with_timestep`].
+    pub fn timestep(&self) -> Duration {
+        self.world().resource::<Time<Fixed>>().timestep()
+    }
+    /// Simulated time elapsed over all steps so far.
+    pub fn elapsed(&self) -> Duration {
+        self.world().resource::<Time<Fixed>>().elapsed()
+    }
+
+    /// Runs the fixed schedules `steps` times, one timestep each.
+    ///
+    /// Physics events such as [`CollisionStarted`] last two steps, as they would over two
+    /// frames in a windowed [`App`].
+    pub fn step(&mut self, steps: u32) {
+        if !self.ready {
+            self.app.finish();
+            self.app.cleanup();
+            self.ready = true;
+        }
+        let world = self.app.world_mut();
+        let timestep = world.resource::<Time<Fixed>>().timestep();
+        for _ in 0..steps {
+            world.resource_mut::<Events<CollisionStarted>>().update();
+            world.resource_mut::<Events<CollisionEnded>>().update();
+            world.resource_mut::<Time<Fixed>>().advance_by(timestep);
+            *world.resource_mut::<Time>() = world.resource::<Time<Fixed>>().as_generic();
+            world.run_schedule(FixedMain);
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use bevy::prelude::*;
+
+use crate::{
+    PhysicsConfig, PhysicsForces,
+    components::{DynamicObject, Shape},
+};
+
+/// How [`DynamicObject`]s are advanced over a [`Time<Fixed>`] step.
+///
+/// This only picks how position and velocity are integrated. Rotation always uses semi-implicit
+/// Euler, and contact forces always change velocity at a steady rate over the step they were
+/// found for.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Integrator {
+    /// Updates velocity first, then moves by the new velocity. Cheap and stable, first order.
+    #[default]
+    SemiImplicitEuler,
+    /// Second order. Velocity is corrected a step late so forces are only evaluated once.
+    VelocityVerlet,
+    /// Classic fourth order Runge-Kutta for position and velocity. Runs [`PhysicsForces`] four
+    /// more times per step.
+    ///
+    /// Forces added outside [`PhysicsForces`], such as contact forces, are held constant
+    /// over the step.
+    Rk4,
+}
+
+/// Acceleration from every force but contacts, which the [`Integrator`] advances.
+fn acceleration(dynamic_object: &DynamicObject) -> Vec2 {
+    force_sum(dynamic_object, false) / dynamic_object.mass
+}
+/// Acceleration from contact forces. Collisions size these for a whole step, so they change
+/// velocity at a steady rate over the step whatever the [`Integrator`], and are never
+/// corrected afterwards or evaluated again.
+fn contact_acceleration(dynamic_object: &DynamicObject) -> Vec2 {
+    force_sum(dynamic_object, true) / dynamic_object.mass
+}
+fn force_sum(dynamic_object: &DynamicObject, contact: bool) -> Vec2 {
+    dynamic_object
+        .forces
+        .iter()
+        .filter(|force| force.label.is_contact() == contact)
+        .map(|force| force.vector)
+        .sum()
+}
+
+pub(crate) fn apply_forces(
+    mut dynamic_objects: Query<(&mut DynamicObject, &Transform, Option<&Shape>)>,
+    config: Res<PhysicsConfig>,
+    time: Res<Time<Fixed>>,
+) {
+    let dt = time.delta_secs();
+    for (mut dynamic_object, transform, shape) in &mut dynamic_objects {
+        if dynamic_object.is_sleeping() {
+            continue;
+        }
+        // Objects without a shape, or whose shape has no area, have nothing to take a moment of
+        // inertia from, so don't spin.
+        if let Some(shape) = shape {
+            let inertia = shape.moment_of_inertia(dynamic_object.mass);
+            let inverse_inertia = if inertia > 0.0 { inertia.recip() } else { 0.0 };
+            let torque = dynamic_object.net_torque(transform.translation.xy());
+            dynamic_object.angular_velocity += torque * inverse_inertia * dt;
+        }
+
+        let acceleration = acceleration(&dynamic_object);
+        match config.integrator {
+            Integrator::SemiImplicitEuler => {
+                let contact = contact_acceleration(&dynamic_object);
+                dynamic_object.velocity += (acceleration + contact) * dt;
+            }
+            Integrator::VelocityVerlet => {
+                // The last step only had its own acceleration to go on, so correct its velocity
+                // to the average of that and the acceleration where the object ended up.
+                if let Some(last_acceleration) = dynamic_object.last_acceleration {
+                    dynamic_object.velocity += (acceleration - last_acceleration) * 0.5 * dt;
+                }
+            }
+            Integrator::Rk4 => {}
+        }
+    }
+}
+
+pub(crate) fn apply_velocity(
+    mut dynamic_objects: Query<(&mut Transform, &mut DynamicObject)>,
+    config: Res<PhysicsConfig>,
+    time: Res<Time<Fixed>>,
+) {
+    let dt = time.delta_secs();
+    for (mut transform, mut dynamic_object) in &mut dynamic_objects {
+        if dynamic_object.is_sleeping() {
+            continue;
+        }
+        transform.rotate_z(dynamic_object.angular_velocity * dt);
+        match config.integrator {
+            Integrator::SemiImplicitEuler => {
+                transform.translation += (dynamic_object.velocity * dt).extend(0.);
+            }
+            Integrator::VelocityVerlet => {
+                let acceleration = acceleration(&dynamic_object);
+                let step_acceleration = acceleration + contact_acceleration(&dynamic_object);
+                transform.translation +=
+                    (dynamic_object.velocity * dt + step_acceleration * 0.5 * dt * dt).extend(0.);
+                dynamic_object.velocity += step_acceleration * dt;
+                dynamic_object.last_acceleration = Some(acceleration);
+            }
+            Integrator::Rk4 => {}
+        }
+    }
+}
+
+struct Rk4Body {
+    entity: Entity,
+    mass: f32,
+    position: Vec2,
+    velocity: Vec2,
+    /// Part of the net force not produced by [`PhysicsForces`] or contacts.
+    constant_force: Vec2,
+    contact_acceleration: Vec2,
+}
+
+/// Sets every body to the given state and returns the acceleration [`PhysicsForces`] gives it.
+fn evaluate(world: &mut World, bodies: &[Rk4Body], states: &[(Vec2, Vec2)]) -> Vec<Vec2> {
+    for (body, (position, velocity)) in bodies.iter().zip(states) {
+        let mut entity = world.entity_mut(body.entity);
+        let mut transform = entity.get_mut::<Transform>().unwrap();
+        transform.translation = position.extend(transform.translation.z);
+        let mut dynamic_object = entity.get_mut::<DynamicObject>().unwrap();
+        dynamic_object.velocity = *velocity;
+        dynamic_object.forces.clear();
+    }
+    world.run_schedule(PhysicsForces);
+    bodies
+        .iter()
+        .map(|body| {
+            let dynamic_object = world.get::<DynamicObject>(body.entity).unwrap();
+            (force_sum(dynamic_object, false) + body.constant_force) / body.mass
+        })
+        .collect()
+}
+
+pub(crate) fn integrate_rk4(world: &mut World) {
+    let dt = world.resource::<Time<Fixed>>().delta_secs();
+
+    let mut query = world.query::<(Entity, &Transform, &DynamicObject)>();
+    let mut forces = Vec::new();
+    let mut sleeping = Vec::new();
+    let mut bodies = Vec::new();
+    let mut initial = Vec::new();
+    let mut initial_accelerations = Vec::new();
+    for (entity, transform, dynamic_object) in query.iter(world) {
+        // Sleeping bodies hold still, so they're left out of the stages.
+        if dynamic_object.is_sleeping() {
+            sleeping.push((entity, dynamic_object.forces.clone()));
+            continue;
+        }
+        forces.push(dynamic_object.forces.clone());
+        initial.push((transform.translation.xy(), dynamic_object.velocity));
+        initial_accelerations.push(acceleration(dynamic_object));
+        bodies.push(Rk4Body {
+            entity,
+            mass: dynamic_object.mass,
+            position: transform.translation.xy(),
+            velocity: dynamic_object.velocity,
+            constant_force: Vec2::ZERO,
+            contact_acceleration: contact_acceleration(dynamic_object),
+        });
+    }
+
+    // Anything this step added outside of PhysicsForces is whatever the schedule doesn't
+    // reproduce from the same state.
+    let scheduled = evaluate(world, &bodies, &initial);
+    for ((body, scheduled), initial) in bodies.iter_mut().zip(scheduled).zip(&initial_accelerations)
+    {
+        body.constant_force = (*initial - scheduled) * body.mass;
+    }
+
+    let stage = |bodies: &[Rk4Body], k: &[(Vec2, Vec2)], h: f32| {
+        bodies
+            .iter()
+            .zip(k)
+            .map(|(body, (dx, dv))| (body.position + *dx * h, body.velocity + *dv * h))
+            .collect::<Vec<_>>()
+    };
+    let derivative = |states: &[(Vec2, Vec2)], accelerations: Vec<Vec2>| {
+        states
+            .iter()
+            .zip(accelerations)
+            .map(|((_, velocity), acceleration)| (*velocity, acceleration))
+            .collect::<Vec<_>>()
+    };
+
+    let k1 = derivative(&initial, initial_accelerations);
+    let s2 = stage(&bodies, &k1, dt * 0.5);
+    let k2 = derivative(&s2, evaluate(world, &bodies, &s2));
+    let s3 = stage(&bodies, &k2, dt * 0.5);
+    let k3 = derivative(&s3, evaluate(world, &bodies, &s3));
+    let s4 = stage(&bodies, &k3, dt);
+    let k4 = derivative(&s4, evaluate(world, &bodies, &s4));
+
+    for (i, (body, forces)) in bodies.iter().zip(forces).enumerate() {
+        let contact = body.contact_acceleration;
+        let dx = (k1[i].0 + 2.0 * k2[i].0 + 2.0 * k3[i].0 + k4[i].0) * dt / 6.0
+            + contact * 0.5 * dt * dt;
+        let dv = (k1[i].1 + 2.0 * k2[i].1 + 2.0 * k3[i].1 + k4[i].1) * dt / 6.0 + contact * dt;
+        let mut entity = world.entity_mut(body.entity);
+        let mut dynamic_object = entity.get_mut::<DynamicObject>().unwrap();
+        // Keep the forces of the real step around for anything drawing them.
+        dynamic_object.forces = forces;
+        dynamic_object.velocity = body.velocity + dv;
+        let mut transform = entity.get_mut::<Transform>().unwrap();
+        transform.translation = (body.position + dx).extend(transform.translation.z);
+    }
+    // The stages still ran PhysicsForces on them.
+    for (entity, forces) in sleeping {
+        world.get_mut::<DynamicObject>(entity).unwrap().forces = forces;
+    }
+}
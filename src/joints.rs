@@ -0,0 +1,117 @@
+use bevy::prelude::*;
+
+use crate::components::{DynamicObject, ForceLabel, JointAnchor, SpringJoint};
+
+/// Where one end of a joint is and how it moves.
+struct End {
+    position: Vec2,
+    velocity: Vec2,
+    /// Zero for ends the joint can't move.
+    inverse_mass: f32,
+}
+impl End {
+    fn fixed(position: Vec2) -> Self {
+        Self {
+            position,
+            velocity: Vec2::ZERO,
+            inverse_mass: 0.0,
+        }
+    }
+    fn of(transform: &Transform, dynamic_object: Option<&DynamicObject>) -> Self {
+        let position = transform.translation.xy();
+        match dynamic_object {
+            Some(dynamic_object) => Self {
+                position,
+                velocity: dynamic_object.velocity,
+                inverse_mass: dynamic_object.inverse_mass(),
+            },
+            None => Self::fixed(position),
+        }
+    }
+}
+
+/// Pulls the ends of every [`SpringJoint`] towards its rest length, damped by how fast they
+/// move apart.
+pub(crate) fn spring_joints(
+    joints: Query<&SpringJoint>,
+    mut bodies: Query<(&Transform, Option<&mut DynamicObject>)>,
+) {
+    for joint in &joints {
+        let end = |anchor| match anchor {
+            JointAnchor::Point(point) => Some(End::fixed(point)),
+            JointAnchor::Entity(entity) => bodies
+                .get(entity)
+                .ok()
+                .map(|(transform, dynamic_object)| End::of(transform, dynamic_object)),
+        };
+        let (Some(a), Some(b)) = (end(joint.a), end(joint.b)) else {
+            continue;
+        };
+
+        let direction = (b.position - a.position).normalize_or_zero();
+        let stretch = a.position.distance(b.position) - joint.rest_length;
+        let separating = (b.velocity - a.velocity).dot(direction);
+        let tension = joint.stiffness * stretch + joint.damping * separating;
+        for (anchor, force) in [
+            (joint.a, direction * tension),
+            (joint.b, -direction * tension),
+        ] {
+            if let JointAnchor::Entity(entity) = anchor
+                && let Ok((_, Some(mut dynamic_object))) = bodies.get_mut(entity)
+            {
+                dynamic_object.add_labeled_force(force, ForceLabel::Spring);
+            }
+        }
+    }
+}
+
+/// Moves the ends of every [`SpringJoint`] back within its length limits and stops them moving
+/// further out, sharing the correction by inverse mass.
+pub(crate) fn limit_joints(
+    joints: Query<&SpringJoint>,
+    mut bodies: Query<(&mut Transform, Option<&mut DynamicObject>)>,
+) {
+    for joint in &joints {
+        let end = |anchor| match anchor {
+            JointAnchor::Point(point) => Some(End::fixed(point)),
+            JointAnchor::Entity(entity) => bodies
+                .get(entity)
+                .ok()
+                .map(|(transform, dynamic_object)| End::of(transform, dynamic_object)),
+        };
+        let (Some(a), Some(b)) = (end(joint.a), end(joint.b)) else {
+            continue;
+        };
+        let total_inverse_mass = a.inverse_mass + b.inverse_mass;
+        if joint.a == joint.b || total_inverse_mass == 0.0 {
+            continue;
+        }
+
+        // `pull` is the way `a` has to go to bring the joint back in range.
+        let length = a.position.distance(b.position);
+        let direction = (b.position - a.position).normalize_or_zero();
+        let (pull, error) = if length > joint.max_length {
+            (direction, length - joint.max_length)
+        } else if length < joint.min_length {
+            (-direction, joint.min_length - length)
+        } else {
+            continue;
+        };
+        let escaping = (b.velocity - a.velocity).dot(pull).max(0.0);
+        let impulse = pull * escaping / total_inverse_mass;
+        let correction = pull * error / total_inverse_mass;
+
+        for (anchor, sign) in [(joint.a, 1.0), (joint.b, -1.0)] {
+            if let JointAnchor::Entity(entity) = anchor
+                && let Ok((mut transform, Some(mut dynamic_object))) = bodies.get_mut(entity)
+            {
+                if dynamic_object.is_sleeping() {
+                    dynamic_object.wake_up();
+                }
+                let inverse_mass = dynamic_object.inverse_mass();
+                transform.translation += (sign * correction * inverse_mass).extend(0.0);
+                dynamic_object.add_impulse(sign * impulse);
+            }
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+
+use crate::components::KinematicObject;
+
+/// Sets the velocities of every [`KinematicObject`] with a path so the step ends at the pose the
+/// path reaches by then.
+pub(crate) fn follow_paths(
+    mut kinematic_objects: Query<(&mut KinematicObject, &Transform)>,
+    time: Res<Time<Fixed>>,
+) {
+    let dt = time.delta_secs();
+    for (mut kinematic_object, transform) in &mut kinematic_objects {
+        let Some(path) = kinematic_object.path.as_mut() else {
+            continue;
+        };
+        path.time += dt;
+        let Some((position, angle)) = path.pose_at(path.time) else {
+            continue;
+        };
+        let current_angle = transform.rotation.to_euler(EulerRot::ZYX).0;
+        // The shorter way round, so angles either side of a full turn don't spin it back.
+        let turn = (angle - current_angle + TAU / 2.0).rem_euclid(TAU) - TAU / 2.0;
+        kinematic_object.velocity = (position - transform.translation.xy()) / dt;
+        kinematic_object.angular_velocity = turn / dt;
+    }
+}
+
+/// Moves every [`KinematicObject`] by its velocities.
+pub(crate) fn move_kinematic_objects(
+    mut kinematic_objects: Query<(&KinematicObject, &mut Transform)>,
+    time: Res<Time<Fixed>>,
+) {
+    let dt = time.delta_secs();
+    for (kinematic_object, mut transform) in &mut kinematic_objects {
+        transform.translation += (kinematic_object.velocity * dt).extend(0.0);
+        transform.rotate_z(kinematic_object.angular_velocity * dt);
+    }
+}
@@ -0,0 +1,274 @@
+use std::time::Duration;
+
+use bevy::{ecs::schedule::ScheduleLabel, prelude::*};
+
+mod broad_phase;
+mod collision;
+mod collision_events;
+pub mod components;
+mod constraints;
+pub mod contact;
+mod continuous;
+mod electromagnetism;
+mod fluid;
+mod force_field;
+mod headless;
+mod integration;
+mod joints;
+mod kinematic;
+mod n_body;
+mod particle_fluid;
+mod sleep;
+mod soft_body;
+mod systems;
+
+pub use broad_phase::BroadPhase;
+pub use collision_events::{CollisionEnded, CollisionStarted, Contacts};
+use components::PhysicsMaterial;
+pub use headless::HeadlessSimulation;
+pub use integration::Integrator;
+pub use n_body::{NBodyGravity, NBodyMethod};
+pub use particle_fluid::ParticleFluid;
+pub use soft_body::SoftBodyBuilder;
+
+/// Groups of the [`FixedUpdate`] physics step, in the order they run.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PhysicsSet {
+    /// Clears the forces left over from the previous step.
+    ClearForces,
+    /// Runs the [`PhysicsForces`] schedule.
+    Forces,
+    /// Detects overlapping shapes and responds to the contacts, then keeps joints within their
+    /// length limits.
+    Collisions,
+    /// Puts resting objects to sleep and wakes disturbed ones.
+    Sleep,
+    /// Turns the accumulated forces into velocity.
+    ApplyForces,
+    /// Moves objects by their velocity.
+    Integrate,
+    /// Pulls objects back into the places their
+    /// [`RigidJoint`](components::RigidJoint)s allow.
+    Constraints,
+}
+
+/// Schedule pushing gravity, spring and other forces onto [`components::DynamicObject`]s.
+///
+/// Run once per step during [`PhysicsSet::Forces`], and again for every extra stage of an
+/// [`Integrator`] that needs the forces at intermediate states. Systems here should only read
+/// positions and velocities and add forces.
+#[derive(ScheduleLabel, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PhysicsForces;
+
+/// Parent set of every [`PhysicsSet`], handy for gating the whole step with one run condition.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PhysicsStepSet;
+
+/// Acceleration applied to every [`components::DynamicObject`], in world units per second
+/// squared, scaled by its [`components::GravityScale`]. Can be changed at any time.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct Gravity(pub Vec2);
+impl Default for Gravity {
+    /// Earth's gravity, treating 100 units as a metre.
+    fn default() -> Self {
+        Self(Vec2::new(0.0, -980.0))
+    }
+}
+
+/// Global tuning shared by the physics systems.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct PhysicsConfig {
+    /// Material of objects without a [`PhysicsMaterial`].
+    pub default_material: PhysicsMaterial,
+    pub integrator: Integrator,
+    pub broad_phase: BroadPhase,
+    /// Whether objects that come to rest fall asleep.
+    pub sleeping: bool,
+    /// Whether objects attract each other, and how.
+    pub n_body_gravity: Option<NBodyGravity>,
+    /// Mass per unit area of the air [`components::Drag`] pushes through.
+    pub air_density: f32,
+    /// Scales the force between [`components::Charge`]s, in world units.
+    pub coulomb_constant: f32,
+    /// How [`components::FluidParticle`]s flow.
+    pub particle_fluid: ParticleFluid,
+}
+impl Default for PhysicsConfig {
+    fn default() -> Self {
+        Self {
+            default_material: PhysicsMaterial::default(),
+            integrator: Integrator::default(),
+            broad_phase: BroadPhase::default(),
+            sleeping: true,
+            n_body_gravity: None,
+            air_density: 0.001,
+            // Two charges of 10 a hundred units apart push about as hard as gravity pulls a
+            // mass of 10.
+            coulomb_constant: 1e6,
+            particle_fluid: ParticleFluid::default(),
+        }
+    }
+}
+
+/// Registers the simulation systems in [`FixedUpdate`].
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use physics_project::PhysicsPlugin;
+/// App::new().add_plugins(
+///     PhysicsPlugin::default()
+///         .with_gravity(Vec2::new(0.0, -162.0))
+///         .with_timestep_hz(120.0),
+/// );
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct PhysicsPlugin {
+    config: PhysicsConfig,
+    gravity: Gravity,
+    timestep: Option<Duration>,
+}
+impl PhysicsPlugin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Sets the starting [`Gravity`].
+    pub fn with_gravity(mut self, gravity: Vec2) -> Self {
+        self.gravity = Gravity(gravity);
+        self
+    }
+    pub fn with_restitution(mut self, restitution: f32) -> Self {
+        self.config.default_material.restitution = restitution;
+        self
+    }
+    pub fn with_default_material(mut self, material: PhysicsMaterial) -> Self {
+        self.config.default_material = material;
+        self
+    }
+    pub fn with_integrator(mut self, integrator: Integrator) -> Self {
+        self.config.integrator = integrator;
+        self
+    }
+    /// Panics if a [`BroadPhase::UniformGrid`] has a cell size that isn't positive and finite.
+    pub fn with_broad_phase(mut self, broad_phase: BroadPhase) -> Self {
+        broad_phase.validate();
+        self.config.broad_phase = broad_phase;
+        self
+    }
+    pub fn with_sleeping(mut self, sleeping: bool) -> Self {
+        self.config.sleeping = sleeping;
+        self
+    }
+    pub fn with_air_density(mut self, air_density: f32) -> Self {
+        self.config.air_density = air_density;
+        self
+    }
+    pub fn with_coulomb_constant(mut self, coulomb_constant: f32) -> Self {
+        self.config.coulomb_constant = coulomb_constant;
+        self
+    }
+    pub fn with_particle_fluid(mut self, particle_fluid: ParticleFluid) -> Self {
+        self.config.particle_fluid = particle_fluid;
+        self
+    }
+    pub fn with_n_body_gravity(mut self, n_body_gravity: NBodyGravity) -> Self {
+        self.config.n_body_gravity = Some(n_body_gravity);
+        self
+    }
+    /// Overrides the length of a [`FixedUpdate`] step. Bevy's default is used otherwise.
+    pub fn with_timestep(mut self, timestep: Duration) -> Self {
+        self.timestep = Some(timestep);
+        self
+    }
+    pub fn with_timestep_hz(self, hz: f64) -> Self {
+        self.with_timestep(Duration::from_secs_f64(hz.recip()))
+    }
+}
+
+impl Plugin for PhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        if let Some(timestep) = self.timestep {
+            app.insert_resource(Time::<Fixed>::from_duration(timestep));
+        }
+        app.insert_resource(self.config)
+            .insert_resource(self.gravity)
+            .init_resource::<Contacts>()
+            .add_event::<CollisionStarted>()
+            .add_event::<CollisionEnded>()
+            .init_schedule(PhysicsForces)
+            .add_systems(
+                PhysicsForces,
+                (
+                    systems::apply_gravity,
+                    n_body::attract,
+                    joints::spring_joints,
+                    fluid::apply_drag,
+                    fluid::apply_fluids,
+                    electromagnetism::push_charges,
+                    electromagnetism::apply_fields,
+                    force_field::apply_force_fields,
+                ),
+            )
+            .configure_sets(
+                FixedUpdate,
+                (
+                    PhysicsSet::ClearForces,
+                    PhysicsSet::Forces,
+                    PhysicsSet::Collisions,
+                    PhysicsSet::Sleep,
+                    PhysicsSet::ApplyForces,
+                    PhysicsSet::Integrate,
+                    PhysicsSet::Constraints,
+                )
+                    .chain()
+                    .in_set(PhysicsStepSet),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    systems::empty_forces.in_set(PhysicsSet::ClearForces),
+                    systems::accumulate_forces.in_set(PhysicsSet::Forces),
+                    (collision::resolve_contacts, joints::limit_joints)
+                        .chain()
+                        .in_set(PhysicsSet::Collisions),
+                    kinematic::follow_paths
+                        .before(PhysicsSet::Collisions)
+                        .in_set(PhysicsStepSet),
+                    sleep::update_sleep
+                        .run_if(|config: Res<PhysicsConfig>| config.sleeping)
+                        .in_set(PhysicsSet::Sleep),
+                    integration::apply_forces.in_set(PhysicsSet::ApplyForces),
+                    (
+                        (
+                            continuous::start_sweeps,
+                            constraints::record_reference_angles,
+                        ),
+                        (
+                            integration::apply_velocity,
+                            integration::integrate_rk4.run_if(|config: Res<PhysicsConfig>| {
+                                config.integrator == Integrator::Rk4
+                            }),
+                        ),
+                        continuous::sweep_circles,
+                    )
+                        .chain()
+                        .in_set(PhysicsSet::Integrate),
+                    kinematic::move_kinematic_objects
+                        .before(continuous::sweep_circles)
+                        .in_set(PhysicsSet::Integrate),
+                    particle_fluid::step_particles.in_set(PhysicsSet::Integrate),
+                    constraints::solve_joints.in_set(PhysicsSet::Constraints),
+                    (
+                        force_field::expire_force_fields,
+                        soft_body::forget_lost_particles,
+                        (
+                            collision::detect_overlaps,
+                            collision_events::send_collision_events,
+                        )
+                            .chain(),
+                    )
+                        .after(PhysicsSet::Constraints)
+                        .in_set(PhysicsStepSet),
+                ),
+            );
+    }
+}
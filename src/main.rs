@@ -1,12 +1,19 @@
-use std::f32::consts::PI;
-
-use bevy::{prelude::*, window::PrimaryWindow};
-use components::{DynamicObject, Force, Shape, SpringConstraint, StaticObject};
-use ops::{atan2, cos, sin};
-
-mod components;
-
-const BOUNCINESS: f32 = 0.8; //0.8999999999;
+use bevy::{
+    prelude::*,
+    render::{
+        mesh::{Indices, PrimitiveTopology},
+        render_asset::RenderAssetUsages,
+    },
+    window::PrimaryWindow,
+};
+use physics_project::{
+    CollisionStarted, Gravity, ParticleFluid, PhysicsPlugin, PhysicsStepSet, SoftBodyBuilder,
+    components::{
+        Charge, DynamicObject, Field, FluidParticle, FluidRegion, ForceField, ForceFieldKind,
+        ForceLabel, JointAnchor, Keyframe, KinematicObject, KinematicPath, PhysicsMaterial,
+        RigidJoint, Sensor, Shape, SoftBody, SoftBodyParticle, StaticObject,
+    },
+};
 
 #[derive(States, Clone, Eq, PartialEq, Hash, Debug)]
 enum SimState {
@@ -16,28 +23,41 @@ enum SimState {
 
 fn main() {
     App::new()
-        .add_systems(Startup, setup_world)
-        .add_systems(Update, render_shapes)
-        .add_systems(Update, wait.run_if(in_state(SimState::Waiting)))
+        .add_systems(Startup, (setup_world, setup_drops))
         .add_systems(
             Update,
-            (spawn_ball, update_cursor_position).run_if(in_state(SimState::Running)),
+            (
+                render_shapes,
+                show_sleeping,
+                show_goal,
+                announce_goals,
+                draw_forces,
+                draw_joints,
+                draw_force_fields,
+                mesh_soft_bodies,
+                deform_soft_bodies,
+                render_drops,
+            ),
         )
+        .add_systems(Update, wait.run_if(in_state(SimState::Waiting)))
         .add_systems(
-            FixedUpdate,
+            Update,
             (
-                (
-                    empty_forces,
-                    (apply_gravity, spring_constraints),
-                    (normal_force, apply_forces).chain(),
-                )
-                    .chain(),
-                apply_velocity,
+                spawn_ball,
+                spawn_charge,
+                spawn_force_field,
+                spawn_soft_body,
+                pour_fluid,
+                update_cursor_position,
+                cycle_gravity,
             )
-                .chain()
                 .run_if(in_state(SimState::Running)),
         )
-        .add_plugins(DefaultPlugins)
+        .configure_sets(
+            FixedUpdate,
+            PhysicsStepSet.run_if(in_state(SimState::Running)),
+        )
+        .add_plugins((DefaultPlugins, PhysicsPlugin::default()))
         .insert_state(SimState::Waiting)
         .init_resource::<CursorCoords>()
         .run();
@@ -54,274 +74,344 @@ fn setup_world(mut commands: Commands) {
             ..OrthographicProjection::default_2d()
         },
     ));
+    // The floor doesn't bounce itself, so balls bounce by their own material and soft bodies,
+    // whose points don't bounce, settle.
     commands.spawn((
         Shape::Rect(10000.0, 50.),
         Transform::from_xyz(0., -500.0, 0.),
         StaticObject {},
+        PhysicsMaterial::new(0.0, 0.5, 0.3),
     ));
     commands.spawn((Shape::Circle(50.0), DynamicObject::new(5.0)));
+    // A pool on the floor, behind everything else. Balls are less dense than it and float.
+    commands.spawn((
+        Shape::Rect(600.0, 300.),
+        Transform::from_xyz(600., -325.0, -1.),
+        FluidRegion::new(0.001, 0.002),
+    ));
+    // A cup standing on the floor to pour fluid into.
+    for x in [-250., -50.] {
+        commands.spawn((
+            Shape::Rect(20., 200.),
+            Transform::from_xyz(x, -375., 0.),
+            StaticObject {},
+        ));
+    }
+    // The inside of the cup lights up while anything is in it.
+    commands.spawn((
+        Shape::Rect(170., 180.),
+        Transform::from_xyz(-150., -380., -1.),
+        Sensor::default(),
+    ));
+    // A magnetic field on the left that curls charged balls' paths.
+    commands.spawn((
+        Shape::Rect(400.0, 600.),
+        Transform::from_xyz(-600., -100.0, -1.),
+        Field::magnetic(20.0),
+    ));
+    // A platform gliding back and forth above the pool, and a paddle turning beside it.
+    let path = KinematicPath::new(vec![
+        Keyframe::new(0.0, Vec2::new(400., -100.)),
+        Keyframe::new(3.0, Vec2::new(800., -100.)),
+        Keyframe::new(6.0, Vec2::new(400., -100.)),
+    ])
+    .repeating();
+    commands.spawn((
+        Shape::Rect(150., 20.),
+        Transform::from_xyz(400., -100., 0.),
+        KinematicObject::default().with_path(path),
+        PhysicsMaterial::new(0.0, 0.8, 0.6),
+    ));
+    commands.spawn((
+        Shape::Rect(150., 20.),
+        Transform::from_xyz(150., -380., 0.),
+        KinematicObject::default().with_angular_velocity(1.5),
+    ));
+}
+
+const SHAPE_COLOR: Color = Color::srgb(1.0, 0., 0.);
+const SLEEPING_COLOR: Color = Color::srgb(0.4, 0.1, 0.1);
+const JOINT_COLOR: Color = Color::srgb(0.7, 0.7, 0.7);
+const FLUID_COLOR: Color = Color::srgba(0.2, 0.4, 1.0, 0.4);
+const FIELD_COLOR: Color = Color::srgba(0.6, 0.2, 1.0, 0.2);
+const FORCE_FIELD_COLOR: Color = Color::srgb(0.2, 1.0, 0.4);
+const SOFT_BODY_COLOR: Color = Color::srgb(1.0, 0.5, 0.5);
+const DROP_COLOR: Color = Color::srgb(0.3, 0.6, 1.0);
+const GOAL_COLOR: Color = Color::srgba(1.0, 0.9, 0.2, 0.1);
+const SCORED_COLOR: Color = Color::srgba(1.0, 0.9, 0.2, 0.4);
+
+/// Mesh and material every [`FluidParticle`] shares, so Bevy draws them all as instances of
+/// one circle.
+#[derive(Resource)]
+struct DropAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<ColorMaterial>,
+}
+
+fn setup_drops(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let spacing = ParticleFluid::default().rest_density.sqrt().recip();
+    commands.insert_resource(DropAssets {
+        mesh: meshes.add(Circle::new(spacing / 2.)),
+        material: materials.add(DROP_COLOR),
+    });
+}
+fn render_drops(
+    drops: Query<Entity, Added<FluidParticle>>,
+    assets: Res<DropAssets>,
+    mut commands: Commands,
+) {
+    for drop in &drops {
+        commands.entity(drop).insert((
+            Mesh2d(assets.mesh.clone()),
+            MeshMaterial2d(assets.material.clone()),
+        ));
+    }
 }
 
+/// Shapes to give a mesh. The points of soft bodies are drawn as part of their body instead.
+type NewShapes<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Shape,
+        Has<FluidRegion>,
+        Has<Field>,
+        Has<Sensor>,
+    ),
+    (Added<Shape>, Without<SoftBodyParticle>),
+>;
+
 fn render_shapes(
-    shapes: Query<(Entity, &Shape), Added<Shape>>,
+    shapes: NewShapes,
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
 ) {
-    for (entity, shape) in &shapes {
+    for (entity, shape, fluid, field, sensor) in &shapes {
         let mesh = meshes.add(match shape {
             Shape::Circle(radius) => Into::<Mesh>::into(Circle::new(*radius)),
             Shape::Rect(width, height) => Rectangle::new(*width, *height).into(),
+            Shape::Polygon(vertices) => polygon_mesh(vertices),
+            Shape::Capsule(radius, length) => Capsule2d::new(*radius, *length).into(),
+            Shape::Segment(start, end) => {
+                Mesh::new(PrimitiveTopology::LineList, RenderAssetUsages::default())
+                    .with_inserted_attribute(
+                        Mesh::ATTRIBUTE_POSITION,
+                        vec![start.extend(0.).to_array(), end.extend(0.).to_array()],
+                    )
+            }
         });
         let mut entity = commands.entity(entity);
-        entity.insert((
-            Mesh2d(mesh),
-            MeshMaterial2d(materials.add(Color::srgb(1.0, 0., 0.))),
-        ));
+        let color = match (fluid, field, sensor) {
+            (true, ..) => FLUID_COLOR,
+            (_, true, _) => FIELD_COLOR,
+            (.., true) => GOAL_COLOR,
+            _ => SHAPE_COLOR,
+        };
+        entity.insert((Mesh2d(mesh), MeshMaterial2d(materials.add(color))));
     }
 }
-fn apply_velocity(
-    mut dynamic_objects: Query<(&mut Transform, &DynamicObject)>,
-    time: Res<Time<Fixed>>,
+/// Dims sleeping objects.
+fn show_sleeping(
+    dynamic_objects: Query<(&DynamicObject, &MeshMaterial2d<ColorMaterial>)>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
 ) {
-    for (mut transform, dynamic_object) in &mut dynamic_objects {
-        transform.translation +=
-            dynamic_object.velocity.extend(0.) * time.delta_secs() * Vec3::splat(2.);
+    for (dynamic_object, material) in &dynamic_objects {
+        let color = if dynamic_object.is_sleeping() {
+            SLEEPING_COLOR
+        } else {
+            SHAPE_COLOR
+        };
+        // Only touch the material when it changes, as every change is sent to the GPU again.
+        if materials
+            .get(&material.0)
+            .is_some_and(|material| material.color != color)
+        {
+            materials.get_mut(&material.0).unwrap().color = color;
+        }
     }
 }
-
-fn apply_forces(mut dynamic_objects: Query<(&mut DynamicObject, &Transform)>, mut gizmos: Gizmos) {
-    for (mut dynamic_object, transform) in &mut dynamic_objects {
-        let mut additional_velocity = Vec2::ZERO;
-        for force in &dynamic_object.forces {
-            let magnitude = force.magnitude / dynamic_object.mass;
-            let acceleration_vector =
-                Vec2::new(magnitude * cos(force.angle), magnitude * sin(force.angle));
-            additional_velocity += acceleration_vector;
-            gizmos.arrow_2d(
-                transform.translation.xy(),
-                transform.translation.xy() + (acceleration_vector),
-                force.color.unwrap_or(Color::srgb(0., 0., 1.)),
-            );
+/// Brightens sensors while something overlaps them.
+fn show_goal(
+    sensors: Query<(&Sensor, &MeshMaterial2d<ColorMaterial>), Changed<Sensor>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for (sensor, material) in &sensors {
+        let color = if sensor.overlapping.is_empty() {
+            GOAL_COLOR
+        } else {
+            SCORED_COLOR
+        };
+        if materials
+            .get(&material.0)
+            .is_some_and(|material| material.color != color)
+        {
+            materials.get_mut(&material.0).unwrap().color = color;
         }
-        // let last_vel = dynamic_object.velocity.length();
-        // gizmos.arrow_2d(transform.translation.xy(), transform.translation.xy() + (additional_velocity), Color::srgb(1., 1., 1.));
-        dynamic_object.velocity += additional_velocity;
     }
 }
-
-fn empty_forces(mut dynamic_objects: Query<&mut DynamicObject>) {
-    for mut dynamic_object in &mut dynamic_objects {
-        let _ = dynamic_object.forces.drain(..);
+/// Logs each ball that lands in a sensor, and every hard knock.
+fn announce_goals(
+    mut collisions: EventReader<CollisionStarted>,
+    sensors: Query<(), With<Sensor>>,
+    balls: Query<(), (With<DynamicObject>, Without<SoftBodyParticle>)>,
+) {
+    for collision in collisions.read() {
+        let in_goal = |sensor, ball| sensors.contains(sensor) && balls.contains(ball);
+        if in_goal(collision.a, collision.b) || in_goal(collision.b, collision.a) {
+            info!("Goal!");
+        } else if collision.impulse > 5000.0 {
+            info!("Crash! Impulse {:.0}", collision.impulse);
+        }
     }
 }
-fn apply_gravity(mut dynamic_objects: Query<&mut DynamicObject>) {
-    for mut dynamic_object in &mut dynamic_objects {
-        let mass = dynamic_object.mass;
-        dynamic_object.forces.push(Force::from_x_and_y(
-            0.0,
-            -9.8 * mass,
-            Some(Color::srgb_u8(199, 165, 14)),
+/// Gives soft bodies with area a mesh of their triangles, which [`deform_soft_bodies`] then
+/// moves with their points.
+fn mesh_soft_bodies(
+    bodies: Query<(Entity, &SoftBody), Changed<SoftBody>>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for (entity, body) in &bodies {
+        if body.triangles.is_empty() {
+            commands
+                .entity(entity)
+                .remove::<(Mesh2d, MeshMaterial2d<ColorMaterial>)>();
+            continue;
+        }
+        let mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_attribute(
+            Mesh::ATTRIBUTE_POSITION,
+            vec![[0.0; 3]; body.particles.len()],
+        )
+        .with_inserted_indices(Indices::U32(body.triangles.concat()));
+        commands.entity(entity).insert((
+            Mesh2d(meshes.add(mesh)),
+            MeshMaterial2d(materials.add(SOFT_BODY_COLOR)),
+            Transform::default(),
         ));
     }
 }
-fn normal_force(
-    mut objects: Query<(Option<&mut DynamicObject>, &mut Transform, &Shape)>,
-    // gizmos: Gizmos,
+/// Moves each soft body's mesh to where its points are, and draws ropes as lines through them.
+/// A body that just lost points keeps its old shape until [`mesh_soft_bodies`] rebuilds it.
+fn deform_soft_bodies(
+    bodies: Query<(&SoftBody, Option<&Mesh2d>)>,
+    transforms: Query<&Transform>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut gizmos: Gizmos,
 ) {
-    let mut objects: Vec<_> = objects.iter_mut().collect();
-    for i in 0..objects.len() {
-        if objects[i].0.is_none() {
-            continue;
-        }
-        'a: for j in 0..objects.len() {
-            if i == j {
-                continue 'a;
+    for (body, mesh) in &bodies {
+        let points = body.particles.iter().filter_map(|particle| {
+            transforms
+                .get(*particle)
+                .ok()
+                .map(|transform| transform.translation.xy())
+        });
+        match mesh.and_then(|mesh| meshes.get_mut(&mesh.0)) {
+            Some(mesh) => {
+                let positions: Vec<_> = points.map(|point| point.extend(0.).to_array()).collect();
+                if positions.len() == mesh.count_vertices() {
+                    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+                }
             }
-            let main_translation = objects[i].1.translation.xy();
-            let other_translation = objects[j].1.translation.xy();
-
-            let intersects =
-                objects[i]
-                    .2
-                    .intersects(main_translation, objects[j].2, other_translation);
-            if intersects {
-                let other_closest_point =
-                    objects[j]
-                        .2
-                        .closest_point(other_translation, objects[i].2, main_translation);
-                let delta_not_normalized = main_translation - other_closest_point;
-                let delta = (main_translation - other_closest_point)
-                    .try_normalize()
-                    .unwrap_or((main_translation - other_translation).normalize_or_zero());
-                let delta_angle = delta.to_angle();
-                // gizmos.arrow_2d(
-                //     main_translation,
-                //     main_translation
-                //         + Vec2::new(cos(delta_angle), sin(delta_angle)) * Vec2::splat(50.0),
-                //     // + (-1.5 * opposing_velocity_magnitude * mass))),
-                //     Color::srgb(0., 0., 0.),
-                // );
-                // objects[i].1.translation += (Vec2::splat(0.1) * delta).extend(0.);
-
-                if let (Some(dynamic_object), dynamic_object_transform, _) = &mut objects[i] {
-                    let opposing_force_magnitude = {
-                        let mut magnitude = 0.;
-                        for force in &dynamic_object.forces {
-                            let adjusted_angle = force.angle - delta_angle;
-                            let mag = cos(adjusted_angle) * force.magnitude;
-                            // gizmos.arrow_2d(
-                            //     main_translation,
-                            //     main_translation
-                            //         + Vec2::new(cos(delta_angle), sin(delta_angle))
-                            //             * Vec2::splat(mag),
-                            //     Color::srgb_u8(14, 32, 199),
-                            // );
-                            magnitude += mag;
-                        }
-                        magnitude
-                    };
-                    // let opposing_velocity_magnitude = {
-                    //     let velocity_magnitude = dynamic_object.velocity.length();
-                    //     let velocity_angle =
-                    //         atan2(dynamic_object.velocity.y, dynamic_object.velocity.x);
-                    //
-                    //     cos(velocity_angle - delta_angle) * velocity_magnitude
-                    // };
-
-                    // dynamic_object.velocity = Vec2::ZERO;
-                    // eprintln!("{}", opposing_force_magnitude);
-
-                    // let mass = dynamic_object.mass;
-                    {
-                        let perpendicular_angle = delta_angle/*  - PI / 2. */;
-
-                        // gizmos.arrow_2d(
-                        //     main_translation,
-                        //     main_translation
-                        //         + Vec2::new(cos(perpendicular_angle), sin(perpendicular_angle))
-                        //             * Vec2::splat(100.),
-                        //     Color::srgb_u8(255, 118, 118),
-                        // );
-
-                        let velocity_angle = dynamic_object.velocity.to_angle();
-                        let velocity_mag = dynamic_object.velocity.length();
-                        // gizmos.arrow_2d(
-                        //     main_translation,
-                        //     main_translation
-                        //         + Vec2::new(cos(velocity_angle), sin(velocity_angle))
-                        //             * Vec2::splat(100.),
-                        //     Color::srgb_u8(255, 118, 118),
-                        // );
-                        let adjusted_angle =
-                            -({ velocity_angle - perpendicular_angle }) + perpendicular_angle;
-                        let new_mag = -velocity_mag * BOUNCINESS;
-                        // if new_mag <= 100.0 {
-                        //     new_mag = 0.;
-                        // }
-                        dynamic_object.velocity =
-                            Vec2::new(cos(adjusted_angle), sin(adjusted_angle))
-                                * Vec2::splat(
-                                    new_mag, //magic number yay
-                                );
-
-                        // gizmos.arrow_2d(
-                        //     main_translation,
-                        //     main_translation
-                        //         + Vec2::new(cos(adjusted_angle), sin(adjusted_angle))
-                        //             * Vec2::splat(velocity_mag * 100.),
-                        //     Color::srgb_u8(255, 255, 255),
-                        // );
-                    };
-                    dynamic_object.forces.push(Force::from_magnitude_and_angle(
-                        -opposing_force_magnitude,
-                        delta_angle,
-                        Some(Color::srgb_u8(199, 14, 187)),
-                    ));
-                    let adjustment_vec = {
-                        let mag = delta_not_normalized.length();
-                        let angle = delta_not_normalized.to_angle();
-
-                        Vec2::from_angle(angle) * mag.recip()
-                    };
-                    // gizmos.arrow_2d(
-                    //     main_translation,
-                    //     main_translation + adjustment_vec * 10.,
-                    //     Color::srgb_u8(255, 255, 255),
-                    // );
-                    dynamic_object_transform.translation += adjustment_vec.extend(0.) * 0.125;
-                    // gizmos.arrow_2d(
-                    //     main_translation,
-                    //     main_translation
-                    //         + Vec2::new(cos(delta_angle), sin(delta_angle))
-                    //             * Vec2::splat(
-                    //                 (-opposing_force_magnitude)
-                    //                     + (-1.5 * opposing_velocity_magnitude * mass),
-                    //             ),
-                    //     Color::srgb(0., 1., 0.),
-                    // );
-
-                    // let opposing_force_magnitude = {
-                    //     let mut magnitude = 0.;
-                    //     for force in &dynamic_object.forces {
-                    //         let adjusted_angle = force.angle - delta_angle;
-                    //         let mag = cos(adjusted_angle) * force.magnitude;
-                    //         magnitude += mag;
-                    //     }
-                    //     magnitude
-                    // };
-                    // eprintln!("after: {}", opposing_force_magnitude);
-                    let normal_force = opposing_force_magnitude.abs();
+            None => gizmos.linestrip_2d(points, SOFT_BODY_COLOR),
+        }
+    }
+}
+/// Fans triangles out from the first vertex, like [`ConvexPolygon`]'s mesh.
+fn polygon_mesh(vertices: &[Vec2]) -> Mesh {
+    let positions: Vec<_> = vertices
+        .iter()
+        .map(|vertex| vertex.extend(0.).to_array())
+        .collect();
+    let indices = (2..vertices.len() as u32)
+        .flat_map(|i| [0, i - 1, i])
+        .collect();
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_indices(Indices::U32(indices))
+}
+fn draw_forces(dynamic_objects: Query<(&DynamicObject, &Transform)>, mut gizmos: Gizmos) {
+    for (dynamic_object, transform) in &dynamic_objects {
+        for force in &dynamic_object.forces {
+            let origin = force.point.unwrap_or(transform.translation.xy());
+            gizmos.arrow_2d(
+                origin,
+                origin + force.vector / dynamic_object.mass,
+                force_color(force.label),
+            );
+        }
+    }
+}
 
-                    let perpendicular_angle = /* 2. *  */delta_angle + PI / 2.0;
+/// Draws rigid joints as lines between their attachment points.
+fn draw_joints(joints: Query<&RigidJoint>, transforms: Query<&Transform>, mut gizmos: Gizmos) {
+    let attachment = |anchor, local: Vec2| match anchor {
+        JointAnchor::Entity(entity) => transforms
+            .get(entity)
+            .ok()
+            .map(|transform| (transform.translation + transform.rotation * local.extend(0.)).xy()),
+        JointAnchor::Point(point) => Some(point + local),
+    };
+    for joint in &joints {
+        if let (Some(a), Some(b)) = (
+            attachment(joint.a, joint.local_a),
+            attachment(joint.b, joint.local_b),
+        ) {
+            gizmos.line_2d(a, b, JOINT_COLOR);
+        }
+    }
+}
 
-                    let velocity_along_tangent_sign = {
-                        // let velocity_magnitude = dynamic_object.velocity.length_squared();
-                        let velocity_angle =
-                            atan2(dynamic_object.velocity.y, dynamic_object.velocity.x);
-                        cos(velocity_angle)
-                    };
-                    dynamic_object.forces.push(Force::from_magnitude_and_angle(
-                        (if velocity_along_tangent_sign > 0.5 {
-                            velocity_along_tangent_sign
-                        } else {
-                            0.
-                        }) * normal_force
-                            * 0.15,
-                        perpendicular_angle,
-                        Some(Color::srgb_u8(199, 14, 187)),
-                    ));
-                    // gizmos.arrow_2d(
-                    //     main_translation,
-                    //     main_translation
-                    //         + Vec2::new(cos(perpendicular_angle), sin(perpendicular_angle))
-                    //             * -Vec2::splat(50. * velocity_along_tangent_sign),
-                    //     Color::srgb(0., 1., 0.),
-                    // );
-                };
-            }
-            // let intersects = {
-            //     let position = objects[i].1.translation.xy();
-            //     match objects[i].2 {
-            //         Shape::Circle(radius) => BoundingCircle::new(position, *radius),
-            //         Shape::Rect(width, height) => {
-            //             Aabb2d::new(position, Vec2::new(width / 2.0, height / 2.0))
-            //         }
-            //     }
-            // }
-            // .intersects({
-            //     let position = objects[j].1.translation.xy();
-            //     match objects[j].2 {
-            //         Shape::Circle(radius) => &BoundingCircle::new(position, *radius),
-            //         Shape::Rect(width, height) => {
-            //             &Aabb2d::new(position, Vec2::new(width / 2.0, height / 2.0))
-            //         }
-            //     }
-            // });
+/// Outlines each force field's range, with arrows showing which way it pushes.
+fn draw_force_fields(fields: Query<(&ForceField, &Transform)>, mut gizmos: Gizmos) {
+    for (field, transform) in &fields {
+        let center = transform.translation.xy();
+        gizmos.circle_2d(center, field.radius, FORCE_FIELD_COLOR);
+        let arrow = field.radius / 2.;
+        for direction in [Vec2::X, Vec2::Y, Vec2::NEG_X, Vec2::NEG_Y] {
+            let Some(force) = field.force_at(direction * arrow) else {
+                continue;
+            };
+            let start = match field.kind {
+                ForceFieldKind::Wind(_) => center + direction.perp() * arrow,
+                _ => center + direction * arrow,
+            };
+            gizmos.arrow_2d(
+                start,
+                start + force.normalize_or_zero() * arrow / 2.,
+                FORCE_FIELD_COLOR,
+            );
         }
     }
 }
 
+fn force_color(label: ForceLabel) -> Color {
+    match label {
+        ForceLabel::Applied => Color::srgb(0., 0., 1.),
+        ForceLabel::Gravity => Color::srgb_u8(199, 165, 14),
+        ForceLabel::Spring => Color::srgb(1.0, 1.0, 1.0),
+        ForceLabel::Normal | ForceLabel::Friction => Color::srgb_u8(199, 14, 187),
+        ForceLabel::Drag => Color::srgb(0., 1., 1.),
+        ForceLabel::Buoyancy => Color::srgb(0.2, 0.6, 1.0),
+        ForceLabel::Electric => Color::srgb(1.0, 0.5, 0.),
+        ForceLabel::Magnetic => Color::srgb(0.6, 0.2, 1.0),
+        ForceLabel::ForceField => FORCE_FIELD_COLOR,
+    }
+}
+
 fn wait(input: Res<ButtonInput<MouseButton>>, mut next_state: ResMut<NextState<SimState>>) {
     if input.just_pressed(MouseButton::Left) {
         next_state.set(SimState::Running);
@@ -358,6 +448,20 @@ fn update_cursor_position(
         coords.0 = world_position;
     }
 }
+/// Gravities G steps through: Earth, the Moon, none and sideways.
+const GRAVITIES: [Vec2; 4] = [
+    Vec2::new(0., -980.),
+    Vec2::new(0., -162.),
+    Vec2::ZERO,
+    Vec2::new(490., 0.),
+];
+
+fn cycle_gravity(input: Res<ButtonInput<KeyCode>>, mut gravity: ResMut<Gravity>) {
+    if input.just_pressed(KeyCode::KeyG) {
+        let current = GRAVITIES.iter().position(|it| *it == gravity.0);
+        gravity.0 = GRAVITIES[current.map_or(0, |i| (i + 1) % GRAVITIES.len())];
+    }
+}
 fn spawn_ball(
     input: Res<ButtonInput<MouseButton>>,
     cursor_pos: Res<CursorCoords>,
@@ -370,59 +474,94 @@ fn spawn_ball(
             Transform::from_xyz(cursor_pos.0.x, cursor_pos.0.y, 0.),
         ));
     }
-    if input.just_pressed(MouseButton::Right) {
-        let a = commands
-            .spawn((
-                Shape::Circle(20.0),
-                DynamicObject::new(5.0),
-                Transform::from_xyz(cursor_pos.0.x + 100., cursor_pos.0.y, 0.),
-            ))
-            .id();
-        // .id();
-        let b = commands
-            .spawn((
+    if input.just_pressed(MouseButton::Middle) {
+        // A chain of balls hanging from the cursor, held out sideways so it swings.
+        let mut above = JointAnchor::Point(cursor_pos.0);
+        for i in 1..=4 {
+            let ball = commands
+                .spawn((
+                    Shape::Circle(15.0),
+                    DynamicObject::new(1.0),
+                    Transform::from_xyz(cursor_pos.0.x + i as f32 * 60., cursor_pos.0.y, 0.),
+                ))
+                .id();
+            commands.spawn(RigidJoint::distance(above, ball, 60.0));
+            above = ball.into();
+        }
+    }
+}
+/// P and N drop a positively or negatively charged ball at the cursor.
+fn spawn_charge(
+    input: Res<ButtonInput<KeyCode>>,
+    cursor_pos: Res<CursorCoords>,
+    mut commands: Commands,
+) {
+    for (key, charge) in [(KeyCode::KeyP, 10.0), (KeyCode::KeyN, -10.0)] {
+        if input.just_pressed(key) {
+            commands.spawn((
                 Shape::Circle(20.0),
                 DynamicObject::new(5.0),
+                Charge(charge),
                 Transform::from_xyz(cursor_pos.0.x, cursor_pos.0.y, 0.),
-                SpringConstraint {
-                    other: a,
-                    strength: 0.25,
-                    length: 200.0,
-                },
-            ))
-            .id();
-
-        commands.entity(a).insert(SpringConstraint {
-            other: b,
-            strength: 0.25,
-            length: 200.0,
-        });
+            ));
+        }
     }
 }
-
-fn spring_constraints(
-    mut objects: Query<(
-        Entity,
-        &mut Transform,
-        &mut DynamicObject,
-        Option<&SpringConstraint>,
-    )>,
+/// A, E, W and V put an attractor, an explosion, a rightward wind or a vortex at the cursor.
+fn spawn_force_field(
+    input: Res<ButtonInput<KeyCode>>,
+    cursor_pos: Res<CursorCoords>,
+    mut commands: Commands,
 ) {
-    let mut objects = objects.iter_mut().collect::<Vec<_>>();
-    for i in 0..objects.len() {
-        if let Some(spring_constraint) = objects[i].3 {
-            let other_translation = objects
-                .iter()
-                .find(|(it, _, _, _)| *it == spring_constraint.other)
-                .unwrap()
-                .1
-                .translation;
-            let current_delta = objects[i].1.translation - other_translation;
-            let distance_from_target = spring_constraint.length - current_delta.length();
-            objects[i].2.forces.push(Force::from_magnitude_and_angle(
-                distance_from_target * spring_constraint.strength,
-                current_delta.xy().to_angle(),
-                Some(Color::srgb(1.0, 1.0, 1.0)),
+    let fields = [
+        (KeyCode::KeyA, ForceField::attractor(20000., 300.)),
+        (KeyCode::KeyE, ForceField::explosion(200000., 300.)),
+        (KeyCode::KeyW, ForceField::wind(Vec2::X, 5000., 300.)),
+        (KeyCode::KeyV, ForceField::vortex(20000., 300.)),
+    ];
+    for (key, field) in fields {
+        if input.just_pressed(key) {
+            commands.spawn((
+                field,
+                Transform::from_xyz(cursor_pos.0.x, cursor_pos.0.y, 0.),
+            ));
+        }
+    }
+}
+/// Right click drops a jelly box at the cursor, B a jelly ball and R a rope.
+fn spawn_soft_body(
+    mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    cursor_pos: Res<CursorCoords>,
+    mut commands: Commands,
+) {
+    let builder = if mouse.just_pressed(MouseButton::Right) {
+        SoftBodyBuilder::rectangle(Vec2::new(150., 100.), 6, 4)
+    } else if keys.just_pressed(KeyCode::KeyB) {
+        SoftBodyBuilder::circle(60., 2, 12)
+    } else if keys.just_pressed(KeyCode::KeyR) {
+        SoftBodyBuilder::rope(300., 15)
+    } else {
+        return;
+    };
+    builder.with_mass(10.).spawn(&mut commands, cursor_pos.0);
+}
+/// F pours a block of fluid from the cursor.
+fn pour_fluid(
+    input: Res<ButtonInput<KeyCode>>,
+    cursor_pos: Res<CursorCoords>,
+    mut commands: Commands,
+) {
+    if !input.just_pressed(KeyCode::KeyF) {
+        return;
+    }
+    let spacing = ParticleFluid::default().rest_density.sqrt().recip();
+    for row in 0..20 {
+        for column in 0..20 {
+            let offset = Vec2::new(column as f32 - 9.5, row as f32 - 9.5) * spacing;
+            commands.spawn((
+                FluidParticle::default(),
+                Transform::from_translation((cursor_pos.0 + offset).extend(0.)),
             ));
         }
     }
@@ -0,0 +1,266 @@
+use bevy::prelude::*;
+
+use crate::{
+    PhysicsConfig,
+    components::{DynamicObject, ForceLabel},
+};
+
+/// Deepest a Barnes–Hut tree splits, so objects at the same spot don't split it forever.
+const MAX_DEPTH: usize = 32;
+
+/// Every [`DynamicObject`] pulling every other by Newton's law of universal gravitation, on top
+/// of the uniform [`Gravity`](crate::Gravity).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NBodyGravity {
+    /// The gravitational constant, in world units.
+    pub constant: f32,
+    /// Length combined with the distance between objects, as `sqrt(d² + softening²)`, so close
+    /// passes don't fling them apart.
+    pub softening: f32,
+    pub method: NBodyMethod,
+}
+impl NBodyGravity {
+    pub fn new(constant: f32) -> Self {
+        Self {
+            constant,
+            softening: 1.0,
+            method: NBodyMethod::default(),
+        }
+    }
+    pub fn with_softening(mut self, softening: f32) -> Self {
+        self.softening = softening;
+        self
+    }
+    pub fn with_method(mut self, method: NBodyMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Acceleration towards `mass` at `offset` away.
+    fn pull(&self, offset: Vec2, mass: f32) -> Vec2 {
+        let softened = offset.length_squared() + self.softening * self.softening;
+        offset * self.constant * mass / (softened * softened.sqrt())
+    }
+}
+
+/// How the pull on each object is added up.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum NBodyMethod {
+    /// Sums the pull of every other object exactly. Quadratic in the number of objects.
+    #[default]
+    Direct,
+    /// Groups distant objects into the cells of a quadtree and pulls towards each cell's centre
+    /// of mass instead. A cell is used whole once its width over its distance is below `theta`,
+    /// so smaller values are slower and more accurate. 0.5 is a common choice.
+    BarnesHut { theta: f32 },
+}
+
+/// A square cell of the Barnes–Hut tree.
+struct Cell {
+    center: Vec2,
+    half_width: f32,
+    mass: f32,
+    center_of_mass: Vec2,
+    /// Indices of the non-empty quarters, empty for a leaf.
+    quarters: Vec<usize>,
+    /// Objects in a leaf.
+    members: Vec<usize>,
+}
+
+struct Tree {
+    cells: Vec<Cell>,
+}
+impl Tree {
+    fn new(bodies: &[(Vec2, f32)]) -> Self {
+        let (min, max) = bodies.iter().fold(
+            (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY)),
+            |(min, max), (position, _)| (min.min(*position), max.max(*position)),
+        );
+        let mut tree = Self { cells: Vec::new() };
+        let half_width = ((max - min).max_element() / 2.0).max(f32::EPSILON);
+        tree.build(
+            bodies,
+            (0..bodies.len()).collect(),
+            (min + max) / 2.0,
+            half_width,
+            0,
+        );
+        tree
+    }
+
+    /// Adds a cell holding `members`, and the cells below it, returning its index.
+    fn build(
+        &mut self,
+        bodies: &[(Vec2, f32)],
+        members: Vec<usize>,
+        center: Vec2,
+        half_width: f32,
+        depth: usize,
+    ) -> usize {
+        let mass = members.iter().map(|&i| bodies[i].1).sum::<f32>();
+        let weighted = members
+            .iter()
+            .map(|&i| bodies[i].0 * bodies[i].1)
+            .sum::<Vec2>();
+        let index = self.cells.len();
+        self.cells.push(Cell {
+            center,
+            half_width,
+            mass,
+            center_of_mass: if mass > 0.0 { weighted / mass } else { center },
+            quarters: Vec::new(),
+            members: Vec::new(),
+        });
+        if members.len() <= 1 || depth == MAX_DEPTH {
+            self.cells[index].members = members;
+            return index;
+        }
+
+        let mut split: [Vec<usize>; 4] = Default::default();
+        for i in members {
+            let side = bodies[i].0.cmpge(center);
+            split[side.x as usize + 2 * side.y as usize].push(i);
+        }
+        let quarter = half_width / 2.0;
+        for (n, members) in split.into_iter().enumerate() {
+            if members.is_empty() {
+                continue;
+            }
+            let offset = Vec2::new(
+                if n % 2 == 1 { quarter } else { -quarter },
+                if n / 2 == 1 { quarter } else { -quarter },
+            );
+            let quarter = self.build(bodies, members, center + offset, quarter, depth + 1);
+            self.cells[index].quarters.push(quarter);
+        }
+        index
+    }
+
+    /// Acceleration of object `i` from everything else in the cell at `index`.
+    fn pull(
+        &self,
+        gravity: &NBodyGravity,
+        theta: f32,
+        bodies: &[(Vec2, f32)],
+        i: usize,
+        index: usize,
+    ) -> Vec2 {
+        let cell = &self.cells[index];
+        let position = bodies[i].0;
+        if cell.quarters.is_empty() {
+            return cell
+                .members
+                .iter()
+                .filter(|&&j| j != i)
+                .map(|&j| gravity.pull(bodies[j].0 - position, bodies[j].1))
+                .sum();
+        }
+        let inside = (position - cell.center).abs().max_element() <= cell.half_width;
+        let offset = cell.center_of_mass - position;
+        if !inside && 2.0 * cell.half_width < theta * offset.length() {
+            return gravity.pull(offset, cell.mass);
+        }
+        cell.quarters
+            .iter()
+            .map(|&quarter| self.pull(gravity, theta, bodies, i, quarter))
+            .sum()
+    }
+}
+
+/// Accelerations every object gets from the others.
+fn pulls(gravity: &NBodyGravity, bodies: &[(Vec2, f32)]) -> Vec<Vec2> {
+    match gravity.method {
+        NBodyMethod::Direct => bodies
+            .iter()
+            .enumerate()
+            .map(|(i, (position, _))| {
+                bodies
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .map(|(_, (other, mass))| gravity.pull(*other - *position, *mass))
+                    .sum()
+            })
+            .collect(),
+        NBodyMethod::BarnesHut { theta } => {
+            if bodies.is_empty() {
+                return Vec::new();
+            }
+            let tree = Tree::new(bodies);
+            (0..bodies.len())
+                .map(|i| tree.pull(gravity, theta, bodies, i, 0))
+                .collect()
+        }
+    }
+}
+
+/// Pushes the pull of every [`DynamicObject`] on every other, if
+/// [`PhysicsConfig::n_body_gravity`] is set.
+pub(crate) fn attract(
+    mut dynamic_objects: Query<(&Transform, &mut DynamicObject)>,
+    config: Res<PhysicsConfig>,
+) {
+    let Some(gravity) = config.n_body_gravity else {
+        return;
+    };
+    let bodies: Vec<_> = dynamic_objects
+        .iter()
+        .map(|(transform, dynamic_object)| (transform.translation.xy(), dynamic_object.mass))
+        .collect();
+    let pulls = pulls(&gravity, &bodies);
+    for ((_, mut dynamic_object), pull) in dynamic_objects.iter_mut().zip(pulls) {
+        let mass = dynamic_object.mass;
+        dynamic_object.add_labeled_force(pull * mass, ForceLabel::Gravity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A deterministic scatter of objects of mixed masses.
+    fn cluster() -> Vec<(Vec2, f32)> {
+        (0..300)
+            .map(|i| {
+                let i = i as f32;
+                let position = Vec2::new((i * 37.0) % 500.0, (i * 91.0) % 300.0);
+                (position, 1.0 + i % 7.0)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn barnes_hut_approaches_the_direct_sum() {
+        let bodies = cluster();
+        let gravity = NBodyGravity::new(1000.0);
+        let exact = pulls(&gravity, &bodies);
+        // Relative to the typical pull, as some objects' pulls nearly cancel.
+        let typical = exact.iter().map(|pull| pull.length()).sum::<f32>() / exact.len() as f32;
+        let mut last_error = f32::INFINITY;
+        for theta in [1.0, 0.5, 0.2] {
+            let approximate = pulls(
+                &gravity.with_method(NBodyMethod::BarnesHut { theta }),
+                &bodies,
+            );
+            let error = exact
+                .iter()
+                .zip(&approximate)
+                .map(|(exact, approximate)| exact.distance(*approximate) / typical)
+                .fold(0.0, f32::max);
+            assert!(error < last_error, "{error} at theta {theta}");
+            last_error = error;
+        }
+        assert!(last_error < 0.01, "{last_error}");
+    }
+
+    #[test]
+    fn barnes_hut_copes_with_objects_on_the_same_spot() {
+        let bodies = vec![(Vec2::ZERO, 1.0), (Vec2::ZERO, 1.0), (Vec2::X, 1.0)];
+        let gravity = NBodyGravity::new(1.0).with_method(NBodyMethod::BarnesHut { theta: 0.5 });
+        let approximate = pulls(&gravity, &bodies);
+        let exact = pulls(&gravity.with_method(NBodyMethod::Direct), &bodies);
+        for (exact, approximate) in exact.iter().zip(&approximate) {
+            assert!(exact.distance(*approximate) < 1e-5);
+        }
+    }
+}
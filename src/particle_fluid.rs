@@ -0,0 +1,445 @@
+use std::f32::consts::PI;
+
+use bevy::{
+    math::bounding::{Aabb2d, BoundingVolume},
+    prelude::*,
+};
+
+use crate::{
+    Gravity, PhysicsConfig,
+    collision::{Solid, layers_interact},
+    components::{CollisionLayers, DynamicObject, FluidParticle, KinematicObject, Shape},
+    contact::isometry,
+};
+
+/// Smoothed-particle hydrodynamics for every [`FluidParticle`]: each drop is pushed apart from
+/// crowded neighbours by pressure and dragged along with them by viscosity.
+///
+/// Defaults give water-like drops settling about 6 units apart, with 100 units as a metre.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParticleFluid {
+    /// How far a drop feels its neighbours.
+    pub smoothing_radius: f32,
+    /// Density, in drops per unit area, the pressure pushes towards. Drops spawned on a grid
+    /// `1 / sqrt(rest_density)` apart start about at rest.
+    pub rest_density: f32,
+    /// How hard drops are pushed apart per unit of density above `rest_density`, and pulled
+    /// together below it. Stiffer fluid squashes less, but needs more `substeps`.
+    pub pressure: f32,
+    /// Extra push between drops that get very close, which keeps them from clumping.
+    pub near_pressure: f32,
+    /// How strongly neighbouring drops even out their velocities.
+    pub viscosity: f32,
+    /// Radius of a drop when colliding with static shapes.
+    pub particle_radius: f32,
+    /// Steps of the fluid per physics step. Pressure waves may only cross a fraction of the
+    /// smoothing radius each of them.
+    pub substeps: usize,
+}
+impl Default for ParticleFluid {
+    fn default() -> Self {
+        Self {
+            smoothing_radius: 16.0,
+            rest_density: 0.026,
+            pressure: 1.9e6,
+            near_pressure: 6.7e4,
+            viscosity: 170.0,
+            particle_radius: 2.0,
+            substeps: 4,
+        }
+    }
+}
+impl ParticleFluid {
+    pub fn with_smoothing_radius(mut self, smoothing_radius: f32) -> Self {
+        self.smoothing_radius = smoothing_radius;
+        self
+    }
+    pub fn with_rest_density(mut self, rest_density: f32) -> Self {
+        self.rest_density = rest_density;
+        self
+    }
+    pub fn with_pressure(mut self, pressure: f32, near_pressure: f32) -> Self {
+        self.pressure = pressure;
+        self.near_pressure = near_pressure;
+        self
+    }
+    pub fn with_viscosity(mut self, viscosity: f32) -> Self {
+        self.viscosity = viscosity;
+        self
+    }
+    pub fn with_particle_radius(mut self, particle_radius: f32) -> Self {
+        self.particle_radius = particle_radius;
+        self
+    }
+    pub fn with_substeps(mut self, substeps: usize) -> Self {
+        self.substeps = substeps;
+        self
+    }
+}
+
+/// Smoothing kernels, each adding up to one over the disc of radius `h` in 2D.
+mod kernel {
+    use super::PI;
+
+    /// Weights the density, peaking sharply at the drop itself.
+    pub(super) fn density(r: f32, h: f32) -> f32 {
+        if r >= h {
+            0.0
+        } else {
+            6.0 / (PI * h.powi(4)) * (h - r).powi(2)
+        }
+    }
+    /// Slope of [`density`] with distance.
+    pub(super) fn density_slope(r: f32, h: f32) -> f32 {
+        if r >= h {
+            0.0
+        } else {
+            -12.0 / (PI * h.powi(4)) * (h - r)
+        }
+    }
+    /// Weights the near density, sharper still so only close drops count.
+    pub(super) fn near_density(r: f32, h: f32) -> f32 {
+        if r >= h {
+            0.0
+        } else {
+            10.0 / (PI * h.powi(5)) * (h - r).powi(3)
+        }
+    }
+    /// Slope of [`near_density`] with distance.
+    pub(super) fn near_density_slope(r: f32, h: f32) -> f32 {
+        if r >= h {
+            0.0
+        } else {
+            -30.0 / (PI * h.powi(5)) * (h - r).powi(2)
+        }
+    }
+    /// Weights viscosity, smooth all the way to the drop itself.
+    pub(super) fn viscosity(r: f32, h: f32) -> f32 {
+        if r >= h {
+            0.0
+        } else {
+            4.0 / (PI * h.powi(8)) * (h * h - r * r).powi(3)
+        }
+    }
+}
+
+/// Drops binned into square cells one smoothing radius wide, so each drop's neighbours are in
+/// the nine cells around it. Cells are hashed into a table twice as long as there are drops,
+/// and the drops sorted by their cell's slot, so finding a cell's drops is one slice.
+struct Grid {
+    cell_size: f32,
+    cells: Vec<IVec2>,
+    /// Where each slot's drops start in `sorted`, and one past the last slot's.
+    starts: Vec<usize>,
+    sorted: Vec<usize>,
+}
+impl Grid {
+    fn new(positions: &[Vec2], cell_size: f32) -> Self {
+        let cells: Vec<_> = positions
+            .iter()
+            .map(|position| (*position / cell_size).floor().as_ivec2())
+            .collect();
+        let slots = 2 * positions.len().max(1);
+        let mut starts = vec![0; slots + 1];
+        for cell in &cells {
+            starts[slot(*cell, slots) + 1] += 1;
+        }
+        for i in 1..starts.len() {
+            starts[i] += starts[i - 1];
+        }
+        let mut next = starts.clone();
+        let mut sorted = vec![0; positions.len()];
+        for (i, cell) in cells.iter().enumerate() {
+            let slot = slot(*cell, slots);
+            sorted[next[slot]] = i;
+            next[slot] += 1;
+        }
+        Self {
+            cell_size,
+            cells,
+            starts,
+            sorted,
+        }
+    }
+    /// Every drop within one cell of `position`, which includes all within a smoothing radius.
+    fn near(&self, position: Vec2) -> impl Iterator<Item = usize> + '_ {
+        let cell = (position / self.cell_size).floor().as_ivec2();
+        let slots = self.starts.len() - 1;
+        (-1..=1)
+            .flat_map(move |x| (-1..=1).map(move |y| cell + IVec2::new(x, y)))
+            .flat_map(move |cell| {
+                let slot = slot(cell, slots);
+                self.sorted[self.starts[slot]..self.starts[slot + 1]]
+                    .iter()
+                    // Other cells can share the slot.
+                    .filter(move |i| self.cells[**i] == cell)
+            })
+            .copied()
+    }
+}
+
+/// Slot of the hash table `cell` goes in.
+fn slot(cell: IVec2, slots: usize) -> usize {
+    let hash = (cell.x as u32).wrapping_mul(73_856_093) ^ (cell.y as u32).wrapping_mul(19_349_663);
+    hash as usize % slots
+}
+
+/// The drops within a smoothing radius of each drop, itself included, with their distances.
+struct Neighbours {
+    /// Where each drop's neighbours start in `found`, and one past the last drop's.
+    starts: Vec<usize>,
+    found: Vec<(usize, f32)>,
+}
+impl Neighbours {
+    fn new(positions: &[Vec2], radius: f32) -> Self {
+        let grid = Grid::new(positions, radius);
+        let mut neighbours = Self {
+            starts: vec![0],
+            found: Vec::new(),
+        };
+        for position in positions {
+            neighbours
+                .found
+                .extend(grid.near(*position).filter_map(|j| {
+                    let distance_squared = position.distance_squared(positions[j]);
+                    (distance_squared < radius * radius).then(|| (j, distance_squared.sqrt()))
+                }));
+            neighbours.starts.push(neighbours.found.len());
+        }
+        neighbours
+    }
+    fn of(&self, i: usize) -> &[(usize, f32)] {
+        &self.found[self.starts[i]..self.starts[i + 1]]
+    }
+}
+
+/// Static shapes drops collide with.
+type Obstacles<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Transform,
+        &'static Shape,
+        Option<&'static KinematicObject>,
+        Option<&'static CollisionLayers>,
+    ),
+    (Without<DynamicObject>, Without<FluidParticle>, Solid),
+>;
+
+/// A shape drops can't enter, with its bounds grown by the drop radius.
+struct Obstacle<'a> {
+    shape: &'a Shape,
+    isometry: Isometry2d,
+    bounds: Aabb2d,
+    kinematic_object: Option<&'a KinematicObject>,
+    layers: Option<&'a CollisionLayers>,
+}
+
+/// Moves every [`FluidParticle`] one physics step, in [`ParticleFluid::substeps`] steps of
+/// gravity, pressure and viscosity, each followed by pushing drops out of static and kinematic
+/// shapes whose [`CollisionLayers`] interact with the drop's.
+pub(crate) fn step_particles(
+    mut particles: Query<(&mut Transform, &mut FluidParticle, Option<&CollisionLayers>)>,
+    obstacles: Obstacles,
+    config: Res<PhysicsConfig>,
+    gravity: Res<Gravity>,
+    time: Res<Time<Fixed>>,
+) {
+    let fluid = config.particle_fluid;
+    let (mut positions, mut velocities): (Vec<_>, Vec<_>) = particles
+        .iter()
+        .map(|(transform, particle, _)| (transform.translation.xy(), particle.velocity))
+        .unzip();
+    let drop_layers: Vec<_> = particles
+        .iter()
+        .map(|(.., layers)| layers.copied())
+        .collect();
+    if positions.is_empty() {
+        return;
+    }
+    let obstacles: Vec<_> = obstacles
+        .iter()
+        .map(|(transform, shape, kinematic_object, layers)| {
+            let isometry = isometry(transform);
+            Obstacle {
+                shape,
+                isometry,
+                bounds: shape
+                    .aabb(isometry)
+                    .grow(Vec2::splat(fluid.particle_radius)),
+                kinematic_object,
+                layers,
+            }
+        })
+        .collect();
+
+    let dt = time.delta_secs() / fluid.substeps.max(1) as f32;
+    let h = fluid.smoothing_radius;
+    let mut densities = vec![(0.0, 0.0); positions.len()];
+    for _ in 0..fluid.substeps.max(1) {
+        for velocity in &mut velocities {
+            *velocity += gravity.0 * dt;
+        }
+        // Pressure is worked out where the drops are about to be, which keeps them from
+        // overshooting into each other.
+        let predicted: Vec<_> = positions
+            .iter()
+            .zip(&velocities)
+            .map(|(position, velocity)| *position + *velocity * dt)
+            .collect();
+        let neighbours = Neighbours::new(&predicted, h);
+
+        for (i, density) in densities.iter_mut().enumerate() {
+            *density = neighbours
+                .of(i)
+                .iter()
+                .fold((0.0, 0.0), |(density, near), (_, r)| {
+                    (
+                        density + kernel::density(*r, h),
+                        near + kernel::near_density(*r, h),
+                    )
+                });
+        }
+
+        let pressure = |(density, near): (f32, f32)| {
+            (
+                fluid.pressure * (density - fluid.rest_density),
+                fluid.near_pressure * near,
+            )
+        };
+        let accelerations: Vec<_> = predicted
+            .iter()
+            .enumerate()
+            .map(|(i, position)| {
+                let (own_pressure, own_near_pressure) = pressure(densities[i]);
+                let mut push = Vec2::ZERO;
+                let mut viscous = Vec2::ZERO;
+                for &(j, r) in neighbours.of(i) {
+                    if j == i {
+                        continue;
+                    }
+                    // Drops on the same spot are pushed apart in some direction.
+                    let direction = if r > 0.0 {
+                        (predicted[j] - *position) / r
+                    } else {
+                        Vec2::from_angle(i as f32)
+                    };
+                    // Shared between the two, so each pushes the other as hard as it's pushed.
+                    let (other_pressure, other_near_pressure) = pressure(densities[j]);
+                    let (density, near) = densities[j];
+                    push +=
+                        direction * kernel::density_slope(r, h) * (own_pressure + other_pressure)
+                            / (2.0 * density);
+                    push += direction
+                        * kernel::near_density_slope(r, h)
+                        * (own_near_pressure + other_near_pressure)
+                        / (2.0 * near);
+                    viscous += (velocities[j] - velocities[i]) * kernel::viscosity(r, h);
+                }
+                push / densities[i].0 + viscous * fluid.viscosity
+            })
+            .collect();
+
+        for (((position, velocity), acceleration), layers) in positions
+            .iter_mut()
+            .zip(&mut velocities)
+            .zip(accelerations)
+            .zip(&drop_layers)
+        {
+            *velocity += acceleration * dt;
+            *position += *velocity * dt;
+            let layers = layers.as_ref();
+            collide(
+                position,
+                velocity,
+                &obstacles,
+                layers,
+                fluid.particle_radius,
+            );
+        }
+    }
+
+    for (((mut transform, mut particle, _), (position, velocity)), (density, _)) in particles
+        .iter_mut()
+        .zip(positions.into_iter().zip(velocities))
+        .zip(densities)
+    {
+        transform.translation = position.extend(transform.translation.z);
+        particle.velocity = velocity;
+        particle.density = density;
+    }
+}
+
+/// Pushes a drop out of any shape it's in and stops it moving further in than the shape moves.
+fn collide(
+    position: &mut Vec2,
+    velocity: &mut Vec2,
+    obstacles: &[Obstacle],
+    layers: Option<&CollisionLayers>,
+    radius: f32,
+) {
+    let drop = Shape::Circle(radius);
+    for obstacle in obstacles {
+        if obstacle.bounds.closest_point(*position) != *position
+            || !layers_interact(layers, obstacle.layers)
+        {
+            continue;
+        }
+        // The normal points from the drop into the shape.
+        let Some(manifold) = drop.contact(*position, obstacle.shape, obstacle.isometry) else {
+            continue;
+        };
+        *position -= manifold.normal * manifold.depth;
+        let surface_velocity = obstacle
+            .kinematic_object
+            .map_or(Vec2::ZERO, |kinematic_object| {
+                kinematic_object.velocity_at_point(*position, obstacle.isometry.translation)
+            });
+        let inwards = (*velocity - surface_velocity).dot(manifold.normal);
+        if inwards > 0.0 {
+            *velocity -= manifold.normal * inwards;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sums `kernel` over a fine grid covering its disc.
+    fn integrate(kernel: fn(f32, f32) -> f32, h: f32) -> f32 {
+        let step = h / 200.0;
+        let mut total = 0.0;
+        for x in -200..200 {
+            for y in -200..200 {
+                let point = (Vec2::new(x as f32, y as f32) + 0.5) * step;
+                total += kernel(point.length(), h) * step * step;
+            }
+        }
+        total
+    }
+
+    #[test]
+    fn kernels_add_up_to_one() {
+        for kernel in [kernel::density, kernel::near_density, kernel::viscosity] {
+            let total = integrate(kernel, 16.0);
+            assert!((total - 1.0).abs() < 1e-3, "{total}");
+        }
+    }
+
+    #[test]
+    fn finds_exactly_the_neighbours_within_the_radius() {
+        let positions: Vec<_> = (0..100)
+            .map(|i| Vec2::new((i * 37 % 100) as f32, (i * 61 % 100) as f32 - 50.0))
+            .collect();
+        let neighbours = Neighbours::new(&positions, 16.0);
+        for (i, position) in positions.iter().enumerate() {
+            let mut found: Vec<_> = neighbours.of(i).iter().map(|(j, _)| *j).collect();
+            found.sort();
+            let within: Vec<_> = (0..positions.len())
+                .filter(|j| positions[*j].distance(*position) < 16.0)
+                .collect();
+            assert_eq!(found, within);
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+
+use crate::components::{DynamicObject, Shape};
+
+/// Objects slower than this count as resting.
+const SLEEP_SPEED: f32 = 5.0;
+/// Objects spinning slower than this, in radians per second, count as resting.
+const SLEEP_ANGULAR_SPEED: f32 = 0.05;
+/// How long an object has to rest before it falls asleep, in seconds.
+const SLEEP_TIME: f32 = 0.5;
+/// Change in acceleration from non-contact forces that wakes a sleeping object.
+const WAKE_ACCELERATION: f32 = 10.0;
+/// Change in angular acceleration from non-contact torques that wakes a sleeping object.
+const WAKE_ANGULAR_ACCELERATION: f32 = 0.1;
+
+/// Whether the object is moving fast enough to stay awake, or to wake what it hits.
+pub(crate) fn is_moving(dynamic_object: &DynamicObject) -> bool {
+    dynamic_object.velocity.length() > SLEEP_SPEED
+        || dynamic_object.angular_velocity.abs() > SLEEP_ANGULAR_SPEED
+}
+
+/// Force and torque from everything but contacts, which a sleeping object doesn't get.
+fn load(dynamic_object: &DynamicObject, center: Vec2) -> (Vec2, f32) {
+    let mut force = Vec2::ZERO;
+    let mut torque = dynamic_object.torque;
+    for contact_free in dynamic_object
+        .forces
+        .iter()
+        .filter(|force| !force.label.is_contact())
+    {
+        force += contact_free.vector;
+        if let Some(point) = contact_free.point {
+            torque += (point - center).perp_dot(contact_free.vector);
+        }
+    }
+    (force, torque)
+}
+
+/// Puts objects that have rested for [`SLEEP_TIME`] to sleep, and wakes sleeping objects whose
+/// velocity was set or whose non-contact forces changed.
+pub(crate) fn update_sleep(
+    mut dynamic_objects: Query<(&mut DynamicObject, &Transform, Option<&Shape>)>,
+    time: Res<Time<Fixed>>,
+) {
+    let dt = time.delta_secs();
+    for (mut dynamic_object, transform, shape) in &mut dynamic_objects {
+        let (force, torque) = load(&dynamic_object, transform.translation.xy());
+        if dynamic_object.is_sleeping() {
+            let (slept_force, slept_torque) = dynamic_object.sleep.load;
+            let inertia = shape.map_or(0.0, |shape| shape.moment_of_inertia(dynamic_object.mass));
+            let disturbed = dynamic_object.velocity != Vec2::ZERO
+                || dynamic_object.angular_velocity != 0.0
+                || (force - slept_force).length() > WAKE_ACCELERATION * dynamic_object.mass
+                || (torque - slept_torque).abs() > WAKE_ANGULAR_ACCELERATION * inertia;
+            if disturbed {
+                dynamic_object.wake_up();
+            }
+            continue;
+        }
+
+        if is_moving(&dynamic_object) {
+            dynamic_object.sleep.resting_for = 0.0;
+            continue;
+        }
+        dynamic_object.sleep.resting_for += dt;
+        if dynamic_object.sleep.resting_for >= SLEEP_TIME {
+            dynamic_object.velocity = Vec2::ZERO;
+            dynamic_object.angular_velocity = 0.0;
+            dynamic_object.last_acceleration = None;
+            dynamic_object.sleep.asleep = true;
+            dynamic_object.sleep.load = (force, torque);
+        }
+    }
+}
@@ -0,0 +1,325 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+
+use crate::components::{
+    DynamicObject, PhysicsMaterial, Shape, SoftBody, SoftBodyParticle, SpringJoint,
+};
+
+/// Shortest a spring squashes to, as a fraction of its rest length, so a hard landing can't
+/// turn the body inside out.
+const MIN_STRETCH: f32 = 0.5;
+/// Longest a spring stretches to, as a multiple of its rest length.
+const MAX_STRETCH: f32 = 1.5;
+
+/// What a spring of a soft body resists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SpringKind {
+    /// Stretching along the edges of the lattice.
+    Structural,
+    /// Shearing of its cells, across their diagonals.
+    Shear,
+    /// Bending, between points two apart.
+    Bend,
+}
+
+/// Builds a [`SoftBody`] out of point-mass [`DynamicObject`]s joined by [`SpringJoint`]s.
+///
+/// Every point gets a small circle [`Shape`], so the body collides all around its edge, and a
+/// hard landing can't push the middle out through it. Points bounce off whatever they hit on
+/// their own, so a soft body settles best on surfaces with little
+/// [`restitution`](PhysicsMaterial::restitution).
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use physics_project::SoftBodyBuilder;
+/// fn spawn_jelly(mut commands: Commands) {
+///     SoftBodyBuilder::rectangle(Vec2::new(120.0, 80.0), 6, 4)
+///         .with_mass(10.0)
+///         .spawn(&mut commands, Vec2::new(0.0, 200.0));
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SoftBodyBuilder {
+    points: Vec<Vec2>,
+    springs: Vec<(usize, usize, SpringKind)>,
+    triangles: Vec<[u32; 3]>,
+    mass: f32,
+    structural_stiffness: f32,
+    shear_stiffness: f32,
+    bend_stiffness: f32,
+    damping: f32,
+    particle_radius: Option<f32>,
+    material: PhysicsMaterial,
+}
+impl SoftBodyBuilder {
+    fn new(points: Vec<Vec2>) -> Self {
+        Self {
+            points,
+            springs: Vec::new(),
+            triangles: Vec::new(),
+            mass: 5.0,
+            structural_stiffness: 800.0,
+            shear_stiffness: 400.0,
+            bend_stiffness: 200.0,
+            damping: 5.0,
+            particle_radius: None,
+            material: PhysicsMaterial::new(0.0, 0.5, 0.3),
+        }
+    }
+
+    /// A grid of `columns` by `rows` points filling a rectangle of `size`.
+    pub fn rectangle(size: Vec2, columns: usize, rows: usize) -> Self {
+        let (columns, rows) = (columns.max(2), rows.max(2));
+        let index = |column: usize, row: usize| row * columns + column;
+        let spacing = size / Vec2::new(columns as f32 - 1.0, rows as f32 - 1.0);
+        let mut builder = Self::new(
+            (0..rows)
+                .flat_map(|row| {
+                    (0..columns).map(move |column| {
+                        Vec2::new(column as f32, row as f32) * spacing - size / 2.0
+                    })
+                })
+                .collect(),
+        );
+        for row in 0..rows {
+            for column in 0..columns {
+                let i = index(column, row);
+                if column + 1 < columns {
+                    builder.link(i, index(column + 1, row), SpringKind::Structural);
+                }
+                if row + 1 < rows {
+                    builder.link(i, index(column, row + 1), SpringKind::Structural);
+                }
+                if column + 2 < columns {
+                    builder.link(i, index(column + 2, row), SpringKind::Bend);
+                }
+                if row + 2 < rows {
+                    builder.link(i, index(column, row + 2), SpringKind::Bend);
+                }
+                if column + 1 < columns && row + 1 < rows {
+                    let (right, up, across) = (
+                        index(column + 1, row),
+                        index(column, row + 1),
+                        index(column + 1, row + 1),
+                    );
+                    builder.link(i, across, SpringKind::Shear);
+                    builder.link(right, up, SpringKind::Shear);
+                    builder.fill(i, right, across);
+                    builder.fill(i, across, up);
+                }
+            }
+        }
+        builder
+    }
+
+    /// A disc of `rings` rings of `segments` points around a point in the middle.
+    pub fn circle(radius: f32, rings: usize, segments: usize) -> Self {
+        let (rings, segments) = (rings.max(1), segments.max(3));
+        // Ring 0 is the middle point, whatever the segment.
+        let index = |ring: usize, segment: usize| {
+            if ring == 0 {
+                0
+            } else {
+                1 + (ring - 1) * segments + segment % segments
+            }
+        };
+        let mut points = vec![Vec2::ZERO];
+        for ring in 1..=rings {
+            points.extend((0..segments).map(|segment| {
+                Vec2::from_angle(TAU * segment as f32 / segments as f32) * radius * ring as f32
+                    / rings as f32
+            }));
+        }
+        let mut builder = Self::new(points);
+        for segment in 0..segments {
+            builder.link(0, index(1, segment), SpringKind::Structural);
+            builder.fill(0, index(1, segment), index(1, segment + 1));
+            for ring in 1..=rings {
+                let i = index(ring, segment);
+                builder.link(i, index(ring, segment + 1), SpringKind::Structural);
+                if segments > 4 {
+                    builder.link(i, index(ring, segment + 2), SpringKind::Bend);
+                }
+                if ring + 2 <= rings {
+                    builder.link(i, index(ring + 2, segment), SpringKind::Bend);
+                }
+                if ring < rings {
+                    let (out, next, out_next) = (
+                        index(ring + 1, segment),
+                        index(ring, segment + 1),
+                        index(ring + 1, segment + 1),
+                    );
+                    builder.link(i, out, SpringKind::Structural);
+                    builder.link(i, out_next, SpringKind::Shear);
+                    builder.link(next, out, SpringKind::Shear);
+                    builder.fill(i, out, out_next);
+                    builder.fill(i, out_next, next);
+                }
+            }
+        }
+        builder
+    }
+
+    /// A horizontal line of `segments` springs, `length` long. Ropes have no area, so no
+    /// [`SoftBody::triangles`].
+    pub fn rope(length: f32, segments: usize) -> Self {
+        let segments = segments.max(1);
+        let mut builder = Self::new(
+            (0..=segments)
+                .map(|i| Vec2::new(length * (i as f32 / segments as f32 - 0.5), 0.0))
+                .collect(),
+        );
+        for i in 0..segments {
+            builder.link(i, i + 1, SpringKind::Structural);
+            if i + 2 <= segments {
+                builder.link(i, i + 2, SpringKind::Bend);
+            }
+        }
+        builder
+    }
+
+    fn link(&mut self, a: usize, b: usize, kind: SpringKind) {
+        self.springs.push((a, b, kind));
+    }
+    fn fill(&mut self, a: usize, b: usize, c: usize) {
+        self.triangles.push([a as u32, b as u32, c as u32]);
+    }
+
+    /// Sets the mass of the whole body. Points more springs pull on get more of it, so springs
+    /// crowding onto one point, like the spokes of a circle, don't shake it apart.
+    pub fn with_mass(mut self, mass: f32) -> Self {
+        self.mass = mass;
+        self
+    }
+    /// Sets the stiffness of the springs resisting stretching, shearing and bending, per unit of
+    /// an average point's mass, so the body wobbles the same however heavy it is.
+    ///
+    /// Each point feels the springs on it together, and too stiff or too damped a body gains
+    /// energy every step until it flies apart. The defaults keep clear of that at 64 steps a
+    /// second.
+    pub fn with_stiffness(mut self, structural: f32, shear: f32, bend: f32) -> Self {
+        self.structural_stiffness = structural;
+        self.shear_stiffness = shear;
+        self.bend_stiffness = bend;
+        self
+    }
+    /// Sets the damping of every spring, per unit of an average point's mass.
+    pub fn with_damping(mut self, damping: f32) -> Self {
+        self.damping = damping;
+        self
+    }
+    /// Sets the radius of the points' circles. Defaults to 0.4 of the shortest spring, so
+    /// neighbouring circles don't touch.
+    pub fn with_particle_radius(mut self, radius: f32) -> Self {
+        self.particle_radius = Some(radius);
+        self
+    }
+
+    /// Spawns the points, springs and the [`SoftBody`] entity holding them, with the body's
+    /// middle at `position`. Returns the [`SoftBody`] entity.
+    pub fn spawn(self, commands: &mut Commands, position: Vec2) -> Entity {
+        let radius = self.particle_radius.unwrap_or_else(|| {
+            self.springs
+                .iter()
+                .map(|(a, b, _)| self.points[*a].distance(self.points[*b]))
+                .fold(f32::INFINITY, f32::min)
+                * 0.4
+        });
+        let body = commands.spawn_empty().id();
+        let mut pulls = vec![0; self.points.len()];
+        for (a, b, _) in &self.springs {
+            pulls[*a] += 1;
+            pulls[*b] += 1;
+        }
+        let mass_per_pull = self.mass / pulls.iter().sum::<usize>() as f32;
+        let particles: Vec<_> = self
+            .points
+            .iter()
+            .zip(&pulls)
+            .map(|(point, pulls)| {
+                commands
+                    .spawn((
+                        DynamicObject::new(mass_per_pull * *pulls as f32),
+                        Transform::from_translation((position + *point).extend(0.0)),
+                        Shape::Circle(radius),
+                        self.material,
+                        SoftBodyParticle { body },
+                    ))
+                    .id()
+            })
+            .collect();
+        // Stiffness and damping are given per unit of mass of an average point.
+        let point_mass = self.mass / self.points.len() as f32;
+        let springs = self
+            .springs
+            .iter()
+            .map(|(a, b, kind)| {
+                let stiffness = match kind {
+                    SpringKind::Structural => self.structural_stiffness,
+                    SpringKind::Shear => self.shear_stiffness,
+                    SpringKind::Bend => self.bend_stiffness,
+                };
+                let rest_length = self.points[*a].distance(self.points[*b]);
+                commands
+                    .spawn(
+                        SpringJoint::new(
+                            particles[*a],
+                            particles[*b],
+                            stiffness * point_mass,
+                            rest_length,
+                        )
+                        .with_damping(self.damping * point_mass)
+                        .with_length_limits(rest_length * MIN_STRETCH, rest_length * MAX_STRETCH),
+                    )
+                    .id()
+            })
+            .collect();
+        commands.entity(body).insert(SoftBody {
+            particles,
+            springs,
+            triangles: self.triangles,
+        });
+        body
+    }
+}
+
+/// Drops despawned points from their [`SoftBody`], along with the triangles using them, so the
+/// triangle indices keep pointing at the right points. Bodies that lost nothing are left
+/// unchanged, so [`Changed<SoftBody>`] only picks out the ones that did.
+pub(crate) fn forget_lost_particles(
+    mut bodies: Query<&mut SoftBody>,
+    particles: Query<(), With<SoftBodyParticle>>,
+) {
+    for mut body in &mut bodies {
+        if body
+            .particles
+            .iter()
+            .all(|&particle| particles.contains(particle))
+        {
+            continue;
+        }
+        let mut kept = 0;
+        let new_indices: Vec<_> = body
+            .particles
+            .iter()
+            .map(|&particle| {
+                particles.contains(particle).then(|| {
+                    kept += 1;
+                    kept - 1
+                })
+            })
+            .collect();
+        let body = &mut *body;
+        body.particles
+            .retain(|&particle| particles.contains(particle));
+        body.triangles = body
+            .triangles
+            .iter()
+            .filter_map(|triangle| {
+                let [a, b, c] = triangle.map(|i| new_indices[i as usize]);
+                Some([a?, b?, c?])
+            })
+            .collect();
+    }
+}
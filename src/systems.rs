@@ -0,0 +1,27 @@
+use bevy::prelude::*;
+
+use crate::{
+    Gravity, PhysicsForces,
+    components::{DynamicObject, ForceLabel, GravityScale},
+};
+
+pub(crate) fn accumulate_forces(world: &mut World) {
+    world.run_schedule(PhysicsForces);
+}
+
+pub(crate) fn empty_forces(mut dynamic_objects: Query<&mut DynamicObject>) {
+    for mut dynamic_object in &mut dynamic_objects {
+        let _ = dynamic_object.forces.drain(..);
+        dynamic_object.torque = 0.0;
+    }
+}
+pub(crate) fn apply_gravity(
+    mut dynamic_objects: Query<(&mut DynamicObject, Option<&GravityScale>)>,
+    gravity: Res<Gravity>,
+) {
+    for (mut dynamic_object, gravity_scale) in &mut dynamic_objects {
+        let scale = gravity_scale.map_or(1.0, |gravity_scale| gravity_scale.0);
+        let mass = dynamic_object.mass;
+        dynamic_object.add_labeled_force(gravity.0 * scale * mass, ForceLabel::Gravity);
+    }
+}
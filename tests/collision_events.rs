@@ -0,0 +1,139 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use physics_project::{
+    CollisionEnded, CollisionStarted, Contacts, HeadlessSimulation, PhysicsPlugin,
+    components::{Sensor, Shape},
+};
+
+/// Every collision event sent over the steps run through it.
+#[derive(Default)]
+struct Log {
+    started: Vec<CollisionStarted>,
+    ended: Vec<CollisionEnded>,
+}
+impl Log {
+    fn step(&mut self, sim: &mut HeadlessSimulation, steps: u32) {
+        for _ in 0..steps {
+            sim.step(1);
+            let world = sim.world();
+            let started = world.resource::<Events<CollisionStarted>>();
+            self.started
+                .extend(started.iter_current_update_events().copied());
+            let ended = world.resource::<Events<CollisionEnded>>();
+            self.ended
+                .extend(ended.iter_current_update_events().copied());
+        }
+    }
+}
+
+fn contacts(sim: &HeadlessSimulation) -> &Contacts {
+    sim.world().resource::<Contacts>()
+}
+
+#[test]
+fn each_bounce_starts_and_ends_a_collision() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default().with_restitution(0.8));
+    let mut log = Log::default();
+    let floor = spawn_floor(&mut sim);
+    let ball = spawn_ball(&mut sim, 10.0, 5.0, Vec2::new(0.0, -300.0));
+
+    log.step(&mut sim, 40);
+    let started = &log.started;
+    assert_eq!(started.len(), 1);
+    let hit = started[0];
+    assert_eq!([hit.a, hit.b].map(|e| e == ball || e == floor), [true; 2]);
+    // Landing at about 570 units a second and leaving at 80% of that.
+    let impact = 5.0 * 570.0 * 1.8;
+    assert!(
+        (hit.impulse - impact).abs() < 0.1 * impact,
+        "impulse {}",
+        hit.impulse
+    );
+    log.step(&mut sim, 8);
+    assert_eq!(log.ended.len(), 1);
+    assert!(!contacts(&sim).is_touching(ball, floor));
+
+    log.step(&mut sim, 64);
+    let bounces = log.started.len();
+    assert!(bounces >= 2, "bounced {bounces} times");
+}
+
+#[test]
+fn resting_contact_carries_the_weight_and_outlasts_sleep() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    let mut log = Log::default();
+    let floor = spawn_floor(&mut sim);
+    let crate_ = spawn_box(&mut sim, Vec2::splat(40.0), 5.0, Vec2::new(0.0, -455.0));
+
+    log.step(&mut sim, 16);
+    let weight = 5.0 * 980.0 / 64.0;
+    let impulse = contacts(&sim).impulse(crate_, floor).unwrap();
+    assert!(
+        (impulse - weight).abs() < 0.05 * weight,
+        "impulse {impulse}"
+    );
+    assert_eq!(contacts(&sim).iter().count(), 1);
+
+    log.step(&mut sim, 64);
+    assert!(is_sleeping(&sim, crate_));
+    assert!(contacts(&sim).is_touching(floor, crate_));
+    assert_eq!(log.started.len(), 1);
+    assert!(log.ended.is_empty());
+}
+
+#[test]
+fn sensors_report_objects_passing_through() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    let mut log = Log::default();
+    let goal = sim
+        .world_mut()
+        .spawn((
+            Shape::Rect(200.0, 100.0),
+            Transform::from_xyz(0.0, -100.0, 0.0),
+            Sensor::default(),
+        ))
+        .id();
+    let ball = spawn_ball(&mut sim, 10.0, 5.0, Vec2::ZERO);
+
+    log.step(&mut sim, 64);
+    let started = &log.started;
+    let ended = &log.ended;
+    assert_eq!(started.len(), 1);
+    assert_eq!(started[0].impulse, 0.0);
+    assert_eq!(ended.len(), 1);
+    for (a, b) in [(started[0].a, started[0].b), (ended[0].a, ended[0].b)] {
+        assert!(a == goal && b == ball || a == ball && b == goal);
+    }
+}
+
+#[test]
+fn despawning_ends_the_collision() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    let mut log = Log::default();
+    spawn_floor(&mut sim);
+    let crate_ = spawn_box(&mut sim, Vec2::splat(40.0), 5.0, Vec2::new(0.0, -455.0));
+    log.step(&mut sim, 4);
+    assert_eq!(log.started.len(), 1);
+
+    sim.world_mut().despawn(crate_);
+    log.step(&mut sim, 1);
+    assert_eq!(log.ended.len(), 1);
+    assert_eq!(contacts(&sim).iter().count(), 0);
+}
+
+#[test]
+fn events_are_dropped_two_steps_after_they_are_sent() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    spawn_floor(&mut sim);
+    spawn_box(&mut sim, Vec2::splat(40.0), 5.0, Vec2::new(0.0, -455.0));
+    sim.step(1);
+    let started =
+        |sim: &HeadlessSimulation| sim.world().resource::<Events<CollisionStarted>>().len();
+    assert_eq!(started(&sim), 1);
+    sim.step(1);
+    assert_eq!(started(&sim), 1);
+    sim.step(1);
+    assert_eq!(started(&sim), 0);
+}
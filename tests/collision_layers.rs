@@ -0,0 +1,130 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use physics_project::{
+    HeadlessSimulation, PhysicsPlugin,
+    components::{CollisionLayers, DynamicObject, Sensor, Shape, StaticObject},
+};
+
+const BALLS: u32 = 1;
+const DEBRIS: u32 = 2;
+
+fn spawn_sensor(sim: &mut HeadlessSimulation, size: Vec2, position: Vec2) -> Entity {
+    sim.world_mut()
+        .spawn((
+            Shape::Rect(size.x, size.y),
+            Transform::from_translation(position.extend(0.)),
+            Sensor::default(),
+        ))
+        .id()
+}
+
+fn sensor(sim: &HeadlessSimulation, entity: Entity) -> &Sensor {
+    sim.world()
+        .get::<Sensor>(entity)
+        .expect("entity should be a Sensor")
+}
+
+#[test]
+fn layers_interact_only_when_each_collides_with_the_other() {
+    let ball = CollisionLayers::new(BALLS, BALLS | DEBRIS);
+    let debris = CollisionLayers::new(DEBRIS, BALLS);
+    assert!(ball.interacts_with(&debris));
+    assert!(!debris.interacts_with(&debris));
+    assert!(!CollisionLayers::new(BALLS, DEBRIS).interacts_with(&ball));
+    assert!(CollisionLayers::ALL.interacts_with(&ball));
+    assert!(!CollisionLayers::NONE.interacts_with(&CollisionLayers::ALL));
+}
+
+#[test]
+fn filtered_out_objects_fall_through_the_floor() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    let floor = spawn_floor(&mut sim);
+    sim.world_mut()
+        .entity_mut(floor)
+        .insert(CollisionLayers::new(u32::MAX, BALLS));
+    let ball = spawn_ball(&mut sim, 10.0, 5.0, Vec2::new(-100.0, -400.0));
+    let debris = spawn_box(&mut sim, Vec2::splat(20.0), 5.0, Vec2::new(100.0, -400.0));
+    sim.world_mut()
+        .entity_mut(ball)
+        .insert(CollisionLayers::new(BALLS, u32::MAX));
+    sim.world_mut()
+        .entity_mut(debris)
+        .insert(CollisionLayers::new(DEBRIS, u32::MAX));
+
+    sim.step(64);
+    assert!(position(&sim, ball).y > -470.0);
+    assert!(position(&sim, debris).y < -600.0);
+}
+
+#[test]
+fn linked_pair_can_overlap_while_still_hitting_others() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default().with_sleeping(false));
+    spawn_floor(&mut sim);
+    // Each in its own group, colliding with everything but the other.
+    let a = spawn_ball(&mut sim, 20.0, 5.0, Vec2::new(0.0, -455.0));
+    let b = spawn_ball(&mut sim, 20.0, 5.0, Vec2::new(10.0, -455.0));
+    sim.world_mut()
+        .entity_mut(a)
+        .insert(CollisionLayers::new(BALLS, !DEBRIS));
+    sim.world_mut()
+        .entity_mut(b)
+        .insert(CollisionLayers::new(DEBRIS, !BALLS));
+    connect_spring(&mut sim, a, b, 50.0, 10.0);
+
+    sim.step(64);
+    assert!(position(&sim, a).distance(position(&sim, b)) < 15.0);
+    for ball in [a, b] {
+        assert!((position(&sim, ball).y + 455.0).abs() < 1.0);
+    }
+}
+
+#[test]
+fn sensor_notices_objects_without_stopping_them() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    let goal = spawn_sensor(&mut sim, Vec2::new(200.0, 100.0), Vec2::new(0.0, -100.0));
+    let ball = spawn_ball(&mut sim, 10.0, 5.0, Vec2::ZERO);
+
+    sim.step(16);
+    assert!(sensor(&sim, goal).overlapping.is_empty());
+    sim.step(16);
+    assert!(sensor(&sim, goal).is_overlapping(ball));
+    // Falling freely the whole time.
+    assert!((velocity(&sim, ball).y + 980.0 * 32.0 / 64.0).abs() < 1.0);
+    sim.step(32);
+    assert!(!sensor(&sim, goal).is_overlapping(ball));
+}
+
+#[test]
+fn sensor_only_notices_the_layers_it_filters_for() {
+    let mut sim = weightless();
+    let goal = spawn_sensor(&mut sim, Vec2::splat(200.0), Vec2::ZERO);
+    sim.world_mut()
+        .entity_mut(goal)
+        .insert(CollisionLayers::new(u32::MAX, BALLS));
+    let ball = sim
+        .world_mut()
+        .spawn((
+            Shape::Circle(10.0),
+            DynamicObject::new(5.0),
+            Transform::default(),
+            CollisionLayers::new(BALLS, u32::MAX),
+        ))
+        .id();
+    let wall = sim
+        .world_mut()
+        .spawn((
+            Shape::Rect(20.0, 400.0),
+            Transform::from_xyz(50.0, 0.0, 0.0),
+            StaticObject {},
+            CollisionLayers::new(DEBRIS, u32::MAX),
+        ))
+        .id();
+    let other = spawn_sensor(&mut sim, Vec2::splat(50.0), Vec2::ZERO);
+
+    sim.step(1);
+    assert_eq!(sensor(&sim, goal).overlapping, vec![ball]);
+    assert!(!sensor(&sim, goal).is_overlapping(wall));
+    assert!(!sensor(&sim, goal).is_overlapping(other));
+}
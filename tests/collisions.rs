@@ -0,0 +1,336 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use physics_project::{
+    BroadPhase, HeadlessSimulation, PhysicsPlugin,
+    components::{ContinuousCollision, DynamicObject, PhysicsMaterial, Shape, StaticObject},
+};
+
+/// Two balls on the x axis flying at each other, in a world without gravity.
+fn head_on(
+    restitution: f32,
+    masses: (f32, f32),
+    speeds: (f32, f32),
+) -> (HeadlessSimulation, Entity, Entity) {
+    let mut sim = HeadlessSimulation::new(
+        PhysicsPlugin::default()
+            .with_gravity(Vec2::ZERO)
+            .with_restitution(restitution),
+    );
+    let a = spawn_ball(&mut sim, 20.0, masses.0, Vec2::new(-100.0, 0.0));
+    let b = spawn_ball(&mut sim, 20.0, masses.1, Vec2::new(100.0, 0.0));
+    set_velocity(&mut sim, a, Vec2::new(speeds.0, 0.0));
+    set_velocity(&mut sim, b, Vec2::new(speeds.1, 0.0));
+    // Long enough for the balls to meet and part again.
+    sim.step(64);
+    (sim, a, b)
+}
+
+#[test]
+fn equal_masses_swap_velocities_in_an_elastic_collision() {
+    let (sim, a, b) = head_on(1.0, (5.0, 5.0), (300.0, -100.0));
+    assert!(velocity(&sim, a).distance(Vec2::new(-100.0, 0.0)) < 1e-2);
+    assert!(velocity(&sim, b).distance(Vec2::new(300.0, 0.0)) < 1e-2);
+}
+
+#[test]
+fn elastic_collision_follows_mass_ratio() {
+    let (m1, m2, u1, u2) = (2.0, 6.0, 400.0, 0.0);
+    let (sim, a, b) = head_on(1.0, (m1, m2), (u1, u2));
+
+    let v1 = (u1 * (m1 - m2) + 2.0 * m2 * u2) / (m1 + m2);
+    let v2 = (u2 * (m2 - m1) + 2.0 * m1 * u1) / (m1 + m2);
+    assert!(
+        (velocity(&sim, a).x - v1).abs() < 1e-2,
+        "{}",
+        velocity(&sim, a)
+    );
+    assert!(
+        (velocity(&sim, b).x - v2).abs() < 1e-2,
+        "{}",
+        velocity(&sim, b)
+    );
+}
+
+#[test]
+fn inelastic_collision_conserves_momentum() {
+    let (m1, m2, u1, u2) = (1.0, 3.0, 500.0, -100.0);
+    let (sim, a, b) = head_on(0.0, (m1, m2), (u1, u2));
+
+    let common = (m1 * u1 + m2 * u2) / (m1 + m2);
+    assert!((velocity(&sim, a).x - common).abs() < 1e-2);
+    assert!((velocity(&sim, b).x - common).abs() < 1e-2);
+}
+
+#[test]
+fn material_restitution_overrides_the_default() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    let floor = spawn_floor(&mut sim);
+    let ball = spawn_ball(&mut sim, 50.0, 5.0, Vec2::ZERO);
+    for entity in [floor, ball] {
+        sim.world_mut()
+            .entity_mut(entity)
+            .insert(PhysicsMaterial::new(0.0, 0.5, 0.3));
+    }
+
+    // Falling 425 units takes a little over 0.9 seconds.
+    sim.step(64);
+    let mut highest = f32::NEG_INFINITY;
+    for _ in 0..64 {
+        sim.step(1);
+        highest = highest.max(position(&sim, ball).y);
+    }
+    assert!(
+        highest < -420.0,
+        "ball without restitution should not bounce, reached {highest}"
+    );
+}
+
+#[test]
+fn sliding_ball_starts_rolling() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    spawn_floor(&mut sim);
+    let ball = spawn_ball(&mut sim, 50.0, 5.0, Vec2::new(0.0, -425.0));
+    set_velocity(&mut sim, ball, Vec2::new(300.0, 0.0));
+
+    let mut last_speed = 300.0;
+    for _ in 0..128 {
+        sim.step(1);
+        let speed = velocity(&sim, ball).x;
+        assert!(
+            speed <= last_speed + 1e-3,
+            "friction should only slow the ball, {speed} > {last_speed}"
+        );
+        last_speed = speed;
+    }
+
+    // Friction trades speed for spin until the contact point stops slipping, which for a disc
+    // leaves two thirds of the speed.
+    let velocity = velocity(&sim, ball);
+    let angular_velocity = angular_velocity(&sim, ball);
+    assert!((velocity.x - 200.0).abs() < 2.0, "{velocity}");
+    assert!(
+        (angular_velocity + velocity.x / 50.0).abs() < 0.05,
+        "ball should roll without slipping, {angular_velocity} rad/s at {velocity}"
+    );
+}
+
+#[test]
+fn sliding_box_stops_without_tipping() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    spawn_floor(&mut sim);
+    let crate_ = spawn_box(
+        &mut sim,
+        Vec2::new(100.0, 100.0),
+        5.0,
+        Vec2::new(0.0, -425.0),
+    );
+    set_velocity(&mut sim, crate_, Vec2::new(300.0, 0.0));
+
+    // Slowing at dynamic friction * g takes about a second.
+    sim.step(128);
+    assert!(
+        velocity(&sim, crate_).length() < 1.0,
+        "{}",
+        velocity(&sim, crate_)
+    );
+    assert!(angular_velocity(&sim, crate_).abs() < 1e-2);
+    assert!(rotation(&sim, crate_).abs() < 0.05);
+}
+
+#[test]
+fn shapes_without_area_slide_without_spinning() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    spawn_floor(&mut sim);
+    let point = spawn_ball(&mut sim, 0.0, 5.0, Vec2::new(-200.0, -400.0));
+    let stick = sim
+        .world_mut()
+        .spawn((
+            Shape::Polygon(vec![Vec2::ZERO, Vec2::X * 50.0, Vec2::X * 100.0]),
+            DynamicObject::new(5.0),
+            Transform::from_xyz(200.0, -400.0, 0.0),
+        ))
+        .id();
+    for entity in [point, stick] {
+        set_velocity(&mut sim, entity, Vec2::new(300.0, 0.0));
+    }
+
+    // Friction pushes on both once they land, but neither has a moment of inertia to turn with.
+    sim.step(128);
+    for entity in [point, stick] {
+        assert!(
+            position(&sim, entity).is_finite(),
+            "{}",
+            position(&sim, entity)
+        );
+        assert!(
+            rotation(&sim, entity).is_finite(),
+            "{}",
+            rotation(&sim, entity)
+        );
+    }
+}
+
+/// A box dropped onto a ramp tilted by `angle`, its bottom face parallel to and just above the
+/// ramp's surface.
+fn box_on_ramp(angle: f32) -> (HeadlessSimulation, Entity) {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    spawn_ramp(&mut sim, angle);
+    let on_surface = Vec2::from_angle(angle).rotate(Vec2::new(0.0, 51.0));
+    let crate_ = spawn_box(&mut sim, Vec2::new(50.0, 50.0), 5.0, on_surface);
+    set_rotation(&mut sim, crate_, angle);
+    (sim, crate_)
+}
+
+#[test]
+fn box_holds_on_a_gentle_ramp() {
+    // tan(0.3) is below the default static friction of 0.5.
+    let (mut sim, crate_) = box_on_ramp(0.3);
+    let start = position(&sim, crate_);
+
+    sim.step(128);
+    assert!(
+        position(&sim, crate_).distance(start) < 2.0,
+        "{} moved from {start}",
+        position(&sim, crate_)
+    );
+    assert!(velocity(&sim, crate_).length() < 1.0);
+    assert!((rotation(&sim, crate_) - 0.3).abs() < 0.01);
+}
+
+#[test]
+fn box_slides_down_a_steep_ramp() {
+    // tan(0.7) is above the default static friction of 0.5, so the box slides at
+    // g (sin θ - μ cos θ).
+    let angle: f32 = 0.7;
+    let (mut sim, crate_) = box_on_ramp(angle);
+
+    sim.step(64);
+    let downhill = -Vec2::from_angle(angle);
+    let expected = 980.0 * (angle.sin() - 0.3 * angle.cos());
+    let speed = velocity(&sim, crate_).dot(downhill);
+    assert!(
+        (speed - expected).abs() < expected * 0.05,
+        "{speed} != {expected}"
+    );
+    assert!((rotation(&sim, crate_) - angle).abs() < 0.01);
+}
+
+#[test]
+fn ball_rolls_down_a_segment() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    sim.world_mut().spawn((
+        Shape::Segment(Vec2::new(-1000.0, 500.0), Vec2::new(1000.0, -500.0)),
+        Transform::default(),
+        StaticObject {},
+    ));
+    let slope = Vec2::new(2.0, -1.0).normalize();
+    let ball = spawn_ball(&mut sim, 20.0, 5.0, slope.perp() * 21.0);
+
+    // Rolling without slipping, a disc speeds up at two thirds of g sin θ.
+    sim.step(64);
+    let expected = 2.0 / 3.0 * 980.0 * -slope.y;
+    let speed = velocity(&sim, ball).dot(slope);
+    assert!(
+        (speed - expected).abs() < expected * 0.05,
+        "{speed} != {expected}"
+    );
+    assert!((angular_velocity(&sim, ball) + speed / 20.0).abs() < 0.1);
+}
+
+#[test]
+fn capsule_lies_still_on_its_side() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    spawn_floor(&mut sim);
+    let rod = sim
+        .world_mut()
+        .spawn((
+            Shape::Capsule(10.0, 200.0),
+            DynamicObject::new(5.0),
+            Transform::from_xyz(0.0, -464.0, 0.0)
+                .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_2)),
+        ))
+        .id();
+
+    sim.step(128);
+    assert!(
+        velocity(&sim, rod).length() < 1.0,
+        "{}",
+        velocity(&sim, rod)
+    );
+    assert!((position(&sim, rod).y + 465.0).abs() < 1.0);
+    assert!((rotation(&sim, rod) - std::f32::consts::FRAC_PI_2).abs() < 0.01);
+}
+
+#[test]
+fn broad_phases_give_the_same_pile() {
+    let pile = |broad_phase| {
+        let mut sim =
+            HeadlessSimulation::new(PhysicsPlugin::default().with_broad_phase(broad_phase));
+        spawn_floor(&mut sim);
+        let balls: Vec<_> = (0..30)
+            .map(|i| {
+                let position = Vec2::new(
+                    (i % 6) as f32 * 45.0 + (i / 6) as f32 * 7.0,
+                    (i / 6) as f32 * 45.0,
+                );
+                spawn_ball(&mut sim, 20.0, 5.0, position)
+            })
+            .collect();
+        sim.step(128);
+        balls
+            .iter()
+            .map(|ball| position(&sim, *ball))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        pile(BroadPhase::SweepAndPrune),
+        pile(BroadPhase::UniformGrid { cell_size: 50.0 })
+    );
+}
+
+#[test]
+fn fast_ball_cannot_tunnel_through_the_floor() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    spawn_floor(&mut sim);
+    let ball = spawn_ball(&mut sim, 5.0, 1.0, Vec2::ZERO);
+    sim.world_mut()
+        .entity_mut(ball)
+        .insert(ContinuousCollision::default());
+    // Over 300 units a step, far more than the floor is thick.
+    set_velocity(&mut sim, ball, Vec2::new(0.0, -20000.0));
+
+    for _ in 0..64 {
+        sim.step(1);
+        assert!(
+            position(&sim, ball).y > -475.0,
+            "ball passed into the floor at {}",
+            position(&sim, ball)
+        );
+    }
+}
+
+#[test]
+fn fast_ball_cannot_pass_a_thin_wall() {
+    let mut sim = weightless();
+    sim.world_mut().spawn((
+        Shape::Segment(Vec2::new(0.0, -100.0), Vec2::new(0.0, 100.0)),
+        Transform::from_xyz(300.0, 0.0, 0.0),
+        StaticObject {},
+    ));
+    let ball = spawn_ball(&mut sim, 5.0, 1.0, Vec2::ZERO);
+    sim.world_mut()
+        .entity_mut(ball)
+        .insert(ContinuousCollision::default());
+    set_velocity(&mut sim, ball, Vec2::new(30000.0, 10.0));
+
+    for _ in 0..16 {
+        sim.step(1);
+        assert!(position(&sim, ball).x < 300.0, "{}", position(&sim, ball));
+    }
+    assert!(
+        velocity(&sim, ball).x < 0.0,
+        "should bounce back off the wall"
+    );
+}
@@ -0,0 +1,122 @@
+#![allow(dead_code)]
+
+use bevy::prelude::*;
+use physics_project::{
+    HeadlessSimulation, PhysicsPlugin,
+    components::{DynamicObject, Shape, SpringJoint, StaticObject},
+};
+
+/// A headless simulation with gravity switched off, for scenes that only test one force.
+pub fn weightless() -> HeadlessSimulation {
+    HeadlessSimulation::new(PhysicsPlugin::default().with_gravity(Vec2::ZERO))
+}
+
+/// The floor spawned by the binary's `setup_world`, its top edge at y = -475.
+pub fn spawn_floor(sim: &mut HeadlessSimulation) -> Entity {
+    sim.world_mut()
+        .spawn((
+            Shape::Rect(10000.0, 50.),
+            Transform::from_xyz(0., -500.0, 0.),
+            StaticObject {},
+        ))
+        .id()
+}
+
+/// A long static slab through the origin, tilted counter-clockwise by `angle` radians. Its top
+/// surface is 25 units from the origin.
+pub fn spawn_ramp(sim: &mut HeadlessSimulation, angle: f32) -> Entity {
+    sim.world_mut()
+        .spawn((
+            Shape::Rect(4000.0, 50.),
+            Transform::from_rotation(Quat::from_rotation_z(angle)),
+            StaticObject {},
+        ))
+        .id()
+}
+
+pub fn spawn_ball(sim: &mut HeadlessSimulation, radius: f32, mass: f32, position: Vec2) -> Entity {
+    sim.world_mut()
+        .spawn((
+            Shape::Circle(radius),
+            DynamicObject::new(mass),
+            Transform::from_translation(position.extend(0.)),
+        ))
+        .id()
+}
+
+pub fn spawn_box(sim: &mut HeadlessSimulation, size: Vec2, mass: f32, position: Vec2) -> Entity {
+    sim.world_mut()
+        .spawn((
+            Shape::Rect(size.x, size.y),
+            DynamicObject::new(mass),
+            Transform::from_translation(position.extend(0.)),
+        ))
+        .id()
+}
+
+/// Links two bodies with an undamped spring joint.
+pub fn connect_spring(
+    sim: &mut HeadlessSimulation,
+    a: Entity,
+    b: Entity,
+    stiffness: f32,
+    length: f32,
+) -> Entity {
+    sim.world_mut()
+        .spawn(SpringJoint::new(a, b, stiffness, length))
+        .id()
+}
+
+pub fn position(sim: &HeadlessSimulation, entity: Entity) -> Vec2 {
+    sim.world()
+        .get::<Transform>(entity)
+        .expect("entity should have a Transform")
+        .translation
+        .xy()
+}
+
+pub fn velocity(sim: &HeadlessSimulation, entity: Entity) -> Vec2 {
+    sim.world()
+        .get::<DynamicObject>(entity)
+        .expect("entity should be a DynamicObject")
+        .velocity
+}
+
+pub fn angular_velocity(sim: &HeadlessSimulation, entity: Entity) -> f32 {
+    sim.world()
+        .get::<DynamicObject>(entity)
+        .expect("entity should be a DynamicObject")
+        .angular_velocity
+}
+
+pub fn is_sleeping(sim: &HeadlessSimulation, entity: Entity) -> bool {
+    sim.world()
+        .get::<DynamicObject>(entity)
+        .expect("entity should be a DynamicObject")
+        .is_sleeping()
+}
+
+/// Counter-clockwise rotation in radians.
+pub fn rotation(sim: &HeadlessSimulation, entity: Entity) -> f32 {
+    sim.world()
+        .get::<Transform>(entity)
+        .expect("entity should have a Transform")
+        .rotation
+        .to_euler(EulerRot::ZYX)
+        .0
+}
+
+pub fn set_velocity(sim: &mut HeadlessSimulation, entity: Entity, velocity: Vec2) {
+    sim.world_mut()
+        .get_mut::<DynamicObject>(entity)
+        .expect("entity should be a DynamicObject")
+        .velocity = velocity;
+}
+
+/// Sets the counter-clockwise rotation in radians.
+pub fn set_rotation(sim: &mut HeadlessSimulation, entity: Entity, angle: f32) {
+    sim.world_mut()
+        .get_mut::<Transform>(entity)
+        .expect("entity should have a Transform")
+        .rotation = Quat::from_rotation_z(angle);
+}
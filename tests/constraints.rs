@@ -0,0 +1,155 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use physics_project::{
+    HeadlessSimulation, PhysicsPlugin,
+    components::{DynamicObject, PhysicsMaterial, RigidJoint},
+};
+
+#[test]
+fn pendulum_keeps_its_length_and_period() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    let start = 0.2_f32;
+    let ball = spawn_ball(
+        &mut sim,
+        10.0,
+        5.0,
+        Vec2::new(start.sin(), -start.cos()) * 200.0,
+    );
+    sim.world_mut()
+        .spawn(RigidJoint::distance(Vec2::ZERO, ball, 200.0));
+
+    // Small swings take 2π sqrt(L / g), about 2.84 seconds.
+    let expected = std::f32::consts::TAU * (200.0_f32 / 980.0).sqrt();
+    let mut crossings = Vec::new();
+    let mut was_right = true;
+    for step in 0..640 {
+        sim.step(1);
+        let position = position(&sim, ball);
+        assert!(
+            (position.length() - 200.0).abs() < 0.5,
+            "{}",
+            position.length()
+        );
+        if (position.x > 0.0) != was_right {
+            was_right = position.x > 0.0;
+            crossings.push(step as f32 / 64.0);
+        }
+    }
+    let period =
+        (crossings[crossings.len() - 1] - crossings[0]) * 2.0 / (crossings.len() - 1) as f32;
+    assert!(
+        (period - expected).abs() < expected * 0.02,
+        "{period} != {expected}"
+    );
+}
+
+#[test]
+fn rope_only_holds_once_taut() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    let ball = spawn_ball(&mut sim, 10.0, 5.0, Vec2::new(0.0, -100.0));
+    sim.world_mut()
+        .spawn(RigidJoint::rope(Vec2::ZERO, ball, 200.0));
+
+    // Falling freely until the rope pulls tight after about 0.45 seconds.
+    sim.step(16);
+    assert!((velocity(&sim, ball).y + 980.0 * 0.25).abs() < 1.0);
+
+    sim.step(240);
+    assert!((position(&sim, ball).y + 200.0).abs() < 0.5);
+}
+
+#[test]
+fn pinned_rod_swings_about_its_end() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    let rod = spawn_box(&mut sim, Vec2::new(200.0, 20.0), 5.0, Vec2::new(100.0, 0.0));
+    sim.world_mut().spawn(
+        RigidJoint::pin(rod, Vec2::ZERO).with_local_anchors(Vec2::new(-100.0, 0.0), Vec2::ZERO),
+    );
+
+    for _ in 0..64 {
+        sim.step(1);
+        let end = position(&sim, rod) + Vec2::from_angle(rotation(&sim, rod)) * -100.0;
+        assert!(end.length() < 0.5, "pinned end moved to {end}");
+    }
+    assert!(angular_velocity(&sim, rod).abs() > 1.0);
+}
+
+#[test]
+fn chain_hangs_with_every_link_in_place() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    let links: Vec<_> = (1..=5)
+        .map(|i| spawn_ball(&mut sim, 10.0, 1.0, Vec2::new(i as f32 * 50.0, 0.0)))
+        .collect();
+    sim.world_mut()
+        .spawn(RigidJoint::distance(Vec2::ZERO, links[0], 50.0));
+    for pair in links.windows(2) {
+        sim.world_mut()
+            .spawn(RigidJoint::distance(pair[0], pair[1], 50.0));
+    }
+
+    sim.step(640);
+    let mut above = Vec2::ZERO;
+    for link in links {
+        let position = position(&sim, link);
+        assert!((position.distance(above) - 50.0).abs() < 1.0);
+        assert!(position.y < above.y);
+        above = position;
+    }
+}
+
+#[test]
+fn prismatic_joint_slides_along_its_axis_only() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    let slider = spawn_box(&mut sim, Vec2::new(40.0, 20.0), 5.0, Vec2::ZERO);
+    sim.world_mut()
+        .spawn(RigidJoint::prismatic(Vec2::ZERO, slider, Vec2::X));
+    set_velocity(&mut sim, slider, Vec2::new(100.0, 100.0));
+    sim.world_mut()
+        .get_mut::<DynamicObject>(slider)
+        .unwrap()
+        .angular_velocity = 3.0;
+
+    sim.step(64);
+    let position = position(&sim, slider);
+    assert!((position.x - 100.0).abs() < 1.0, "{position}");
+    assert!(position.y.abs() < 0.5, "{position}");
+    assert!(rotation(&sim, slider).abs() < 0.01);
+}
+
+#[test]
+fn newtons_cradle_passes_the_swing_along() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default().with_sleeping(false));
+    let balls: Vec<_> = (0..3)
+        .map(|i| {
+            let pivot = Vec2::new(i as f32 * 40.0, 0.0);
+            let ball = spawn_ball(&mut sim, 20.0, 5.0, pivot - Vec2::Y * 200.0);
+            sim.world_mut()
+                .entity_mut(ball)
+                .insert(PhysicsMaterial::new(1.0, 0.0, 0.0));
+            sim.world_mut()
+                .spawn(RigidJoint::distance(pivot, ball, 200.0));
+            ball
+        })
+        .collect();
+    // Pull the first ball out to the side and let go.
+    let pulled = Vec2::new(-200.0 * 0.5_f32.sin(), -200.0 * 0.5_f32.cos());
+    sim.world_mut()
+        .get_mut::<Transform>(balls[0])
+        .unwrap()
+        .translation = pulled.extend(0.0);
+
+    // A quarter of a swing to hit the others, and a quarter more for the last to rise.
+    let mut highest = f32::NEG_INFINITY;
+    for _ in 0..96 {
+        sim.step(1);
+        highest = highest.max(position(&sim, balls[2]).y);
+    }
+    assert!(highest > -200.0 + 20.0, "last ball only rose to {highest}");
+    assert!(
+        velocity(&sim, balls[0]).length() < 50.0,
+        "first ball should have stopped, {}",
+        velocity(&sim, balls[0])
+    );
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use physics_project::{
+    HeadlessSimulation, Integrator, PhysicsPlugin,
+    components::{Charge, Field, Shape},
+};
+
+fn charged_ball(sim: &mut HeadlessSimulation, mass: f32, charge: f32, position: Vec2) -> Entity {
+    let ball = spawn_ball(sim, 10.0, mass, position);
+    sim.world_mut().entity_mut(ball).insert(Charge(charge));
+    ball
+}
+
+/// Weightless, with charges not pushing each other.
+fn fields_only() -> HeadlessSimulation {
+    HeadlessSimulation::new(
+        PhysicsPlugin::default()
+            .with_gravity(Vec2::ZERO)
+            .with_coulomb_constant(0.0),
+    )
+}
+
+fn spawn_field(sim: &mut HeadlessSimulation, field: Field) {
+    sim.world_mut()
+        .spawn((Shape::Rect(1000.0, 1000.0), Transform::default(), field));
+}
+
+#[test]
+fn like_charges_repel_and_unlike_attract() {
+    let mut sim = weightless();
+    let [a, b] = [-100.0, 100.0].map(|x| charged_ball(&mut sim, 5.0, 10.0, Vec2::new(x, 0.0)));
+    let c = charged_ball(&mut sim, 5.0, 10.0, Vec2::new(-100.0, 10000.0));
+    let d = charged_ball(&mut sim, 5.0, -10.0, Vec2::new(100.0, 10000.0));
+
+    // 1e6 × 10 × 10 / 200², over a mass of 5 for a step.
+    let kick = 2500.0 / 5.0 / 64.0;
+    sim.step(1);
+    assert!(velocity(&sim, a).distance(Vec2::new(-kick, 0.0)) < 0.1);
+    assert!(velocity(&sim, b).distance(Vec2::new(kick, 0.0)) < 0.1);
+    assert!(velocity(&sim, c).distance(Vec2::new(kick, 0.0)) < 0.1);
+    assert!(velocity(&sim, d).distance(Vec2::new(-kick, 0.0)) < 0.1);
+}
+
+#[test]
+fn charges_without_a_body_still_push() {
+    let mut sim = weightless();
+    sim.world_mut().spawn((Transform::default(), Charge(-10.0)));
+    let ball = charged_ball(&mut sim, 5.0, 10.0, Vec2::new(100.0, 0.0));
+
+    sim.step(1);
+    assert!(velocity(&sim, ball).x < 0.0);
+    assert_eq!(velocity(&sim, ball).y, 0.0);
+}
+
+#[test]
+fn electric_field_pushes_charges_inside_it() {
+    let mut sim = fields_only();
+    spawn_field(&mut sim, Field::electric(Vec2::new(100.0, 0.0)));
+    let inside = charged_ball(&mut sim, 5.0, 2.0, Vec2::ZERO);
+    let outside = charged_ball(&mut sim, 5.0, 2.0, Vec2::new(0.0, 2000.0));
+    let uncharged = spawn_ball(&mut sim, 10.0, 5.0, Vec2::new(0.0, 200.0));
+
+    sim.step(64);
+    assert!(velocity(&sim, inside).distance(Vec2::new(40.0, 0.0)) < 1e-3);
+    assert_eq!(velocity(&sim, outside), Vec2::ZERO);
+    // Fields don't collide, so it isn't pushed out of one either.
+    assert_eq!(velocity(&sim, uncharged), Vec2::ZERO);
+}
+
+#[test]
+fn magnetic_field_turns_charges_in_a_circle() {
+    // Euler steps gain a little speed every step of a turn, so it takes RK4 to keep the circle.
+    let mut sim = HeadlessSimulation::new(
+        PhysicsPlugin::default()
+            .with_gravity(Vec2::ZERO)
+            .with_integrator(Integrator::Rk4),
+    );
+    spawn_field(&mut sim, Field::magnetic(1.0));
+    let ball = charged_ball(&mut sim, 1.0, 1.0, Vec2::ZERO);
+    set_velocity(&mut sim, ball, Vec2::new(100.0, 0.0));
+
+    // A radius of m v / q B = 100, clockwise around (0, -100), taking 2π m / q B seconds.
+    let half_turn = (std::f32::consts::PI * 64.0).round() as usize;
+    for _ in 0..half_turn {
+        sim.step(1);
+        let position = position(&sim, ball);
+        assert!((position.distance(Vec2::new(0.0, -100.0)) - 100.0).abs() < 1.0);
+        assert!((velocity(&sim, ball).length() - 100.0).abs() < 1.0);
+    }
+    assert!(position(&sim, ball).distance(Vec2::new(0.0, -200.0)) < 2.0);
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use physics_project::{HeadlessSimulation, PhysicsPlugin};
+
+#[test]
+fn free_fall_accelerates_uniformly() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    let ball = spawn_ball(&mut sim, 50.0, 5.0, Vec2::ZERO);
+
+    let mut last_position = position(&sim, ball);
+    let mut last_velocity = velocity(&sim, ball);
+    let mut first_delta = None;
+    for _ in 0..120 {
+        sim.step(1);
+        let position = position(&sim, ball);
+        let velocity = velocity(&sim, ball);
+
+        assert!(position.x.abs() < 1e-3);
+        assert!(velocity.x.abs() < 1e-3);
+        assert!(position.y < last_position.y, "ball should keep falling");
+
+        let delta = velocity.y - last_velocity.y;
+        assert!(delta < 0.0);
+        let first_delta = *first_delta.get_or_insert(delta);
+        assert!(
+            (delta - first_delta).abs() < 1e-3,
+            "gravity should change velocity by the same amount every step, {delta} != {first_delta}"
+        );
+
+        last_position = position;
+        last_velocity = velocity;
+    }
+}
+
+#[test]
+fn free_fall_does_not_depend_on_mass() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    let light = spawn_ball(&mut sim, 50.0, 1.0, Vec2::new(-200.0, 0.0));
+    let heavy = spawn_ball(&mut sim, 50.0, 100.0, Vec2::new(200.0, 0.0));
+
+    sim.step(64);
+
+    assert!((position(&sim, light).y - position(&sim, heavy).y).abs() < 1e-3);
+    assert!((velocity(&sim, light).y - velocity(&sim, heavy).y).abs() < 1e-3);
+}
+
+#[test]
+fn ball_comes_to_rest_on_floor() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    spawn_floor(&mut sim);
+    let ball = spawn_ball(&mut sim, 50.0, 5.0, Vec2::ZERO);
+
+    // Ten seconds at the default 64Hz timestep.
+    sim.step(640);
+
+    let resting_height = -475.0 + 50.0;
+    let position = position(&sim, ball);
+    assert!(
+        (position.y - resting_height).abs() < 10.0,
+        "ball should sit on the floor at y = {resting_height}, found {position}"
+    );
+    assert!(position.x.abs() < 1.0);
+    assert!(
+        velocity(&sim, ball).length() < 1.0,
+        "ball should have stopped bouncing, velocity {}",
+        velocity(&sim, ball)
+    );
+}
+
+#[test]
+fn spring_oscillates_about_rest_length() {
+    let mut sim = weightless();
+    let a = spawn_ball(&mut sim, 20.0, 5.0, Vec2::new(-150.0, 0.0));
+    let b = spawn_ball(&mut sim, 20.0, 5.0, Vec2::new(150.0, 0.0));
+    connect_spring(&mut sim, a, b, 32.0, 200.0);
+
+    let mut shortest = f32::INFINITY;
+    let mut longest = 0.0_f32;
+    let mut total = 0.0;
+    let mut crossings = 0;
+    let mut was_stretched = true;
+    let steps = 640;
+    for _ in 0..steps {
+        sim.step(1);
+        let length = position(&sim, a).distance(position(&sim, b));
+        shortest = shortest.min(length);
+        longest = longest.max(length);
+        total += length;
+
+        let stretched = length > 200.0;
+        if stretched != was_stretched {
+            crossings += 1;
+            was_stretched = stretched;
+        }
+
+        // Equal and opposite spring forces must leave the centre of mass where it started.
+        let centre = (position(&sim, a) + position(&sim, b)) / 2.0;
+        assert!(centre.length() < 1e-2, "centre of mass drifted to {centre}");
+    }
+
+    assert!(shortest < 200.0 && longest > 200.0);
+    assert!(
+        crossings >= 4,
+        "spring should oscillate, crossed rest length {crossings} times"
+    );
+    let mean = total / steps as f32;
+    assert!(
+        (mean - 200.0).abs() < 20.0,
+        "spring should oscillate about its rest length, mean length {mean}"
+    );
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use physics_project::{
+    HeadlessSimulation, PhysicsPlugin,
+    components::{Drag, FluidRegion, Shape},
+};
+
+/// Falls for long enough to stop speeding up.
+fn terminal_velocity(drag: Drag) -> f32 {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    let ball = spawn_ball(&mut sim, 20.0, 5.0, Vec2::ZERO);
+    sim.world_mut().entity_mut(ball).insert(drag);
+    sim.step(640);
+    -velocity(&sim, ball).y
+}
+
+#[test]
+fn quadratic_drag_reaches_terminal_velocity() {
+    // m g = ½ρ C w v², with the ball 40 wide and the default air density.
+    let expected = (2.0 * 5.0 * 980.0 / (0.001 * 1.0 * 40.0_f32)).sqrt();
+    let speed = terminal_velocity(Drag::new(0.0, 1.0));
+    assert!(
+        (speed - expected).abs() < expected * 0.01,
+        "{speed} != {expected}"
+    );
+}
+
+#[test]
+fn linear_drag_reaches_terminal_velocity() {
+    let expected = 5.0 * 980.0 / 10.0;
+    let speed = terminal_velocity(Drag::new(10.0, 0.0));
+    assert!(
+        (speed - expected).abs() < expected * 0.01,
+        "{speed} != {expected}"
+    );
+}
+
+/// A deep pool whose surface is at y = 0.
+fn pool(sim: &mut HeadlessSimulation) {
+    sim.world_mut().spawn((
+        Shape::Rect(2000.0, 1000.0),
+        Transform::from_xyz(0.0, -500.0, 0.0),
+        FluidRegion::new(0.001, 0.005),
+    ));
+}
+
+#[test]
+fn box_floats_at_its_density() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    pool(&mut sim);
+    // Half the density of the fluid, so it floats half under.
+    let crate_ = spawn_box(
+        &mut sim,
+        Vec2::new(100.0, 100.0),
+        5.0,
+        Vec2::new(0.0, 100.0),
+    );
+
+    sim.step(640);
+    let position = position(&sim, crate_);
+    assert!(position.y.abs() < 2.0, "{position}");
+    assert!(velocity(&sim, crate_).length() < 1.0);
+    assert!(rotation(&sim, crate_).abs() < 0.01);
+}
+
+#[test]
+fn dense_ball_sinks() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    pool(&mut sim);
+    let ball = spawn_ball(&mut sim, 20.0, 50.0, Vec2::new(0.0, 100.0));
+
+    sim.step(128);
+    assert!(position(&sim, ball).y < -200.0, "{}", position(&sim, ball));
+    assert!(velocity(&sim, ball).y < 0.0);
+}
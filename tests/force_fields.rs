@@ -0,0 +1,79 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use physics_project::{
+    HeadlessSimulation,
+    components::{Falloff, ForceField},
+};
+
+/// Change in velocity from a force of `force` on a mass of 5 over a step.
+fn kick(force: f32) -> f32 {
+    force / 5.0 / 64.0
+}
+
+fn spawn_field(sim: &mut HeadlessSimulation, field: ForceField) -> Entity {
+    sim.world_mut().spawn((field, Transform::default())).id()
+}
+
+#[test]
+fn attractor_pulls_objects_in_range() {
+    let mut sim = weightless();
+    spawn_field(&mut sim, ForceField::attractor(500.0, 200.0));
+    let near = spawn_ball(&mut sim, 10.0, 5.0, Vec2::new(100.0, 0.0));
+    let far = spawn_ball(&mut sim, 10.0, 5.0, Vec2::new(0.0, 300.0));
+
+    sim.step(1);
+    assert!(velocity(&sim, near).distance(Vec2::new(-kick(500.0), 0.0)) < 1e-4);
+    assert_eq!(velocity(&sim, far), Vec2::ZERO);
+}
+
+#[test]
+fn falloff_weakens_the_push_towards_the_edge() {
+    let mut sim = weightless();
+    let linear = ForceField::attractor(500.0, 200.0).with_falloff(Falloff::Linear);
+    spawn_field(&mut sim, linear);
+    let quadratic = linear.with_falloff(Falloff::Quadratic);
+    sim.world_mut()
+        .spawn((quadratic, Transform::from_xyz(0.0, 1000.0, 0.0)));
+    let [a, b] = [0.0, 1000.0].map(|y| spawn_ball(&mut sim, 10.0, 5.0, Vec2::new(100.0, y)));
+
+    sim.step(1);
+    assert!((velocity(&sim, a).x + kick(250.0)).abs() < 1e-4);
+    assert!((velocity(&sim, b).x + kick(125.0)).abs() < 1e-4);
+}
+
+#[test]
+fn wind_and_vortices_push_sideways() {
+    let mut sim = weightless();
+    spawn_field(&mut sim, ForceField::vortex(500.0, 200.0));
+    sim.world_mut().spawn((
+        ForceField::wind(Vec2::new(0.0, -3.0), 500.0, 200.0),
+        Transform::from_xyz(0.0, 1000.0, 0.0),
+    ));
+    let swirled = spawn_ball(&mut sim, 10.0, 5.0, Vec2::new(100.0, 0.0));
+    let blown = spawn_ball(&mut sim, 10.0, 5.0, Vec2::new(100.0, 1000.0));
+
+    sim.step(1);
+    // Counter-clockwise around the centre.
+    assert!(velocity(&sim, swirled).distance(Vec2::new(0.0, kick(500.0))) < 1e-4);
+    assert!(velocity(&sim, blown).distance(Vec2::new(0.0, -kick(500.0))) < 1e-4);
+}
+
+#[test]
+fn explosion_blasts_objects_away_then_goes() {
+    let mut sim = weightless();
+    let explosion = spawn_field(&mut sim, ForceField::explosion(5000.0, 200.0));
+    let ball = spawn_ball(&mut sim, 10.0, 5.0, Vec2::new(-100.0, 0.0));
+
+    // A tenth of a second is 7 steps.
+    sim.step(6);
+    assert!(sim.world().get_entity(explosion).is_ok());
+    sim.step(1);
+    assert!(sim.world().get_entity(explosion).is_err());
+    let speed = velocity(&sim, ball);
+    assert!(speed.x < -kick(2500.0) * 6.0 && speed.y == 0.0, "{speed}");
+
+    sim.step(10);
+    assert_eq!(velocity(&sim, ball), speed);
+}
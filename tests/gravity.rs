@@ -0,0 +1,45 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use physics_project::{Gravity, HeadlessSimulation, PhysicsPlugin, components::GravityScale};
+
+#[test]
+fn gravity_sets_the_fall() {
+    let mut sim =
+        HeadlessSimulation::new(PhysicsPlugin::default().with_gravity(Vec2::new(0.0, -162.0)));
+    let ball = spawn_ball(&mut sim, 10.0, 5.0, Vec2::ZERO);
+
+    sim.step(64);
+    assert!(velocity(&sim, ball).distance(Vec2::new(0.0, -162.0)) < 1e-2);
+}
+
+#[test]
+fn gravity_scale_multiplies_gravity() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    let [heavy, floating, buoyant] =
+        [(2.0, -100.0), (0.0, 0.0), (-0.5, 100.0)].map(|(scale, x)| {
+            let ball = spawn_ball(&mut sim, 10.0, 5.0, Vec2::new(x, 0.0));
+            sim.world_mut().entity_mut(ball).insert(GravityScale(scale));
+            ball
+        });
+
+    sim.step(64);
+    assert!(velocity(&sim, heavy).distance(Vec2::new(0.0, -1960.0)) < 1e-2);
+    assert_eq!(velocity(&sim, floating), Vec2::ZERO);
+    assert!(velocity(&sim, buoyant).distance(Vec2::new(0.0, 490.0)) < 1e-2);
+}
+
+#[test]
+fn changing_gravity_wakes_and_pushes_resting_objects() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    spawn_floor(&mut sim);
+    let ball = spawn_ball(&mut sim, 50.0, 5.0, Vec2::new(0.0, -400.0));
+    sim.step(256);
+    assert!(is_sleeping(&sim, ball));
+
+    sim.world_mut().resource_mut::<Gravity>().0 = Vec2::new(-980.0, 0.0);
+    sim.step(32);
+    assert!(!is_sleeping(&sim, ball));
+    assert!(velocity(&sim, ball).x < -100.0, "{}", velocity(&sim, ball));
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use physics_project::{HeadlessSimulation, Integrator, PhysicsPlugin};
+
+const INTEGRATORS: [Integrator; 3] = [
+    Integrator::SemiImplicitEuler,
+    Integrator::VelocityVerlet,
+    Integrator::Rk4,
+];
+
+fn simulation(integrator: Integrator, hz: f64, gravity: Vec2) -> HeadlessSimulation {
+    HeadlessSimulation::new(
+        PhysicsPlugin::default()
+            .with_gravity(gravity)
+            .with_integrator(integrator)
+            .with_timestep_hz(hz),
+    )
+}
+
+/// Distance between two equal masses on a spring after one second, compared with
+/// `L + (d0 - L) cos(ωt)` where `ω = sqrt(2k / m)`.
+fn spring_error(integrator: Integrator, hz: u32) -> f32 {
+    let (strength, length, mass, start) = (32.0, 200.0, 5.0, 300.0);
+    let mut sim = simulation(integrator, hz as f64, Vec2::ZERO);
+    let a = spawn_ball(&mut sim, 20.0, mass, Vec2::new(-start / 2.0, 0.0));
+    let b = spawn_ball(&mut sim, 20.0, mass, Vec2::new(start / 2.0, 0.0));
+    connect_spring(&mut sim, a, b, strength, length);
+
+    sim.step(hz);
+
+    let omega = (2.0 * strength / mass).sqrt();
+    let expected = length + (start - length) * omega.cos();
+    let distance = position(&sim, a).distance(position(&sim, b));
+    (distance - expected).abs()
+}
+
+#[test]
+fn spring_converges_as_timestep_shrinks() {
+    for integrator in INTEGRATORS {
+        // Any finer and RK4 runs into the precision of f32 positions.
+        let errors = [4, 8, 16, 32].map(|hz| spring_error(integrator, hz));
+        for pair in errors.windows(2) {
+            assert!(
+                pair[1] < pair[0],
+                "{integrator:?} should get closer to the exact solution with smaller steps, errors {errors:?}"
+            );
+        }
+        assert!(
+            errors[3] * 10.0 < errors[0],
+            "{integrator:?} errors {errors:?}"
+        );
+    }
+}
+
+#[test]
+fn higher_order_integrators_are_more_accurate() {
+    let euler = spring_error(Integrator::SemiImplicitEuler, 64);
+    let verlet = spring_error(Integrator::VelocityVerlet, 64);
+    let rk4 = spring_error(Integrator::Rk4, 64);
+    assert!(verlet < euler, "verlet {verlet} vs euler {euler}");
+    assert!(rk4 < verlet, "rk4 {rk4} vs verlet {verlet}");
+}
+
+#[test]
+fn free_fall_matches_kinematics() {
+    let gravity = Vec2::new(0.0, -980.0);
+    for integrator in INTEGRATORS {
+        let errors = [32, 64, 128].map(|hz| {
+            let mut sim = simulation(integrator, hz as f64, gravity);
+            let ball = spawn_ball(&mut sim, 50.0, 5.0, Vec2::ZERO);
+            sim.step(hz);
+
+            // One second of uniform acceleration from rest.
+            let expected_velocity = gravity;
+            let expected_position = gravity / 2.0;
+            assert!(
+                velocity(&sim, ball).distance(expected_velocity) < 1e-2,
+                "{integrator:?} at {hz}Hz: velocity {}",
+                velocity(&sim, ball)
+            );
+            position(&sim, ball).distance(expected_position)
+        });
+
+        match integrator {
+            // Off by half a step of velocity, halving with the timestep.
+            Integrator::SemiImplicitEuler => {
+                assert!(errors[1] < errors[0] && errors[2] < errors[1], "{errors:?}");
+            }
+            // Exact for constant acceleration.
+            Integrator::VelocityVerlet | Integrator::Rk4 => {
+                assert!(errors.iter().all(|error| *error < 1e-2), "{errors:?}");
+            }
+        }
+    }
+}
+
+#[test]
+fn ball_comes_to_rest_on_the_floor() {
+    for integrator in INTEGRATORS {
+        let mut sim = HeadlessSimulation::new(
+            PhysicsPlugin::default()
+                .with_integrator(integrator)
+                .with_sleeping(false),
+        );
+        spawn_floor(&mut sim);
+        let ball = spawn_ball(&mut sim, 50.0, 5.0, Vec2::new(0.0, -400.0));
+        sim.step(320);
+
+        let resting = position(&sim, ball);
+        assert!((resting.y + 425.0).abs() < 1.0, "{integrator:?}: {resting}");
+        for _ in 0..320 {
+            sim.step(1);
+            let (y, speed) = (position(&sim, ball).y, velocity(&sim, ball).length());
+            assert!((y - resting.y).abs() < 0.01, "{integrator:?}: y {y}");
+            assert!(speed < 0.1, "{integrator:?}: speed {speed}");
+        }
+    }
+}
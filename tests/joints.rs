@@ -0,0 +1,103 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use physics_project::{
+    HeadlessSimulation, PhysicsPlugin,
+    components::{JointAnchor, SpringJoint, StaticObject},
+};
+
+#[test]
+fn damped_spring_settles_at_rest_length() {
+    // Sleeping would stop the last slow creep short of the exact length.
+    let mut sim = HeadlessSimulation::new(
+        PhysicsPlugin::default()
+            .with_gravity(Vec2::ZERO)
+            .with_sleeping(false),
+    );
+    let a = spawn_ball(&mut sim, 20.0, 5.0, Vec2::new(-150.0, 0.0));
+    let b = spawn_ball(&mut sim, 20.0, 5.0, Vec2::new(150.0, 0.0));
+    sim.world_mut()
+        .spawn(SpringJoint::new(a, b, 32.0, 200.0).with_damping(10.0));
+
+    sim.step(640);
+    let length = position(&sim, a).distance(position(&sim, b));
+    assert!((length - 200.0).abs() < 1.0, "{length}");
+    assert!(velocity(&sim, a).length() < 1.0);
+    assert!(velocity(&sim, b).length() < 1.0);
+}
+
+#[test]
+fn ball_hangs_from_a_world_point() {
+    // Sleeping would stop the last slow creep short of the exact length.
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default().with_sleeping(false));
+    let ball = spawn_ball(&mut sim, 20.0, 5.0, Vec2::new(0.0, -100.0));
+    sim.world_mut()
+        .spawn(SpringJoint::new(Vec2::ZERO, ball, 50.0, 100.0).with_damping(10.0));
+
+    // Hanging still, the spring is stretched until it holds the ball's weight.
+    sim.step(640);
+    let expected = -100.0 - 5.0 * 980.0 / 50.0;
+    let position = position(&sim, ball);
+    assert!(
+        position.distance(Vec2::new(0.0, expected)) < 1.0,
+        "{position} != {expected}"
+    );
+}
+
+#[test]
+fn rope_holds_a_ball_below_a_static_anchor() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    let anchor = sim
+        .world_mut()
+        .spawn((Transform::from_xyz(0.0, 200.0, 0.0), StaticObject {}))
+        .id();
+    let ball = spawn_ball(&mut sim, 20.0, 5.0, Vec2::new(0.0, 200.0));
+    sim.world_mut()
+        .spawn(SpringJoint::new(anchor, ball, 0.0, 0.0).with_length_limits(0.0, 150.0));
+
+    for _ in 0..128 {
+        sim.step(1);
+        let length = position(&sim, ball).distance(Vec2::new(0.0, 200.0));
+        // The rope pulls back after the step that crosses its length.
+        assert!(length < 160.0, "{length}");
+    }
+    assert!((position(&sim, ball).y - 50.0).abs() < 1.0);
+    assert!(velocity(&sim, ball).length() < 1.0);
+    assert_eq!(position(&sim, anchor), Vec2::new(0.0, 200.0));
+}
+
+#[test]
+fn minimum_length_keeps_balls_apart() {
+    let mut sim = weightless();
+    let a = spawn_ball(&mut sim, 20.0, 5.0, Vec2::new(-200.0, 0.0));
+    let b = spawn_ball(&mut sim, 20.0, 5.0, Vec2::new(200.0, 0.0));
+    set_velocity(&mut sim, a, Vec2::new(200.0, 0.0));
+    set_velocity(&mut sim, b, Vec2::new(-200.0, 0.0));
+    sim.world_mut()
+        .spawn(SpringJoint::new(a, b, 0.0, 0.0).with_length_limits(100.0, f32::INFINITY));
+
+    sim.step(128);
+    let length = position(&sim, a).distance(position(&sim, b));
+    assert!((length - 100.0).abs() < 1.0, "{length}");
+    assert!(velocity(&sim, a).length() < 1e-3);
+    assert!(velocity(&sim, b).length() < 1e-3);
+}
+
+#[test]
+fn joint_with_a_despawned_end_does_nothing() {
+    let mut sim = weightless();
+    let a = spawn_ball(&mut sim, 20.0, 5.0, Vec2::new(-150.0, 0.0));
+    let b = spawn_ball(&mut sim, 20.0, 5.0, Vec2::new(150.0, 0.0));
+    let joint = connect_spring(&mut sim, a, b, 32.0, 200.0);
+    sim.step(1);
+    sim.world_mut().despawn(b);
+
+    let moving = velocity(&sim, a);
+    sim.step(64);
+    assert_eq!(velocity(&sim, a), moving);
+    assert_eq!(
+        sim.world().get::<SpringJoint>(joint).unwrap().b,
+        JointAnchor::Entity(b)
+    );
+}
@@ -0,0 +1,160 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use physics_project::{
+    HeadlessSimulation, PhysicsPlugin,
+    components::{Keyframe, KinematicObject, KinematicPath, PhysicsMaterial, Shape},
+};
+
+fn spawn_kinematic(
+    sim: &mut HeadlessSimulation,
+    size: Vec2,
+    position: Vec2,
+    kinematic_object: KinematicObject,
+) -> Entity {
+    sim.world_mut()
+        .spawn((
+            Shape::Rect(size.x, size.y),
+            kinematic_object,
+            PhysicsMaterial::new(0.0, 0.8, 0.6),
+            Transform::from_translation(position.extend(0.)),
+        ))
+        .id()
+}
+
+fn kinematic_velocity(sim: &HeadlessSimulation, entity: Entity) -> Vec2 {
+    sim.world()
+        .get::<KinematicObject>(entity)
+        .expect("entity should be a KinematicObject")
+        .velocity
+}
+
+#[test]
+fn path_passes_through_its_keyframes() {
+    let mut sim = weightless();
+    let path = KinematicPath::new(vec![
+        Keyframe::new(0.0, Vec2::ZERO),
+        Keyframe::new(1.0, Vec2::new(128.0, 0.0)).with_angle(1.0),
+        Keyframe::new(2.0, Vec2::ZERO),
+    ])
+    .repeating();
+    let platform = spawn_kinematic(
+        &mut sim,
+        Vec2::new(100.0, 20.0),
+        Vec2::ZERO,
+        KinematicObject::default().with_path(path),
+    );
+
+    sim.step(32);
+    assert!(position(&sim, platform).distance(Vec2::new(64.0, 0.0)) < 1e-3);
+    assert!((rotation(&sim, platform) - 0.5).abs() < 1e-4);
+    assert!(kinematic_velocity(&sim, platform).distance(Vec2::new(128.0, 0.0)) < 1e-2);
+    // A quarter of the way into the second lap.
+    sim.step(64 + 32 + 16);
+    assert!(position(&sim, platform).distance(Vec2::new(32.0, 0.0)) < 1e-2);
+    assert!(kinematic_velocity(&sim, platform).distance(Vec2::new(128.0, 0.0)) < 1e-1);
+}
+
+#[test]
+fn path_without_repeat_stops_at_the_last_keyframe() {
+    let mut sim = weightless();
+    let path = KinematicPath::new(vec![
+        Keyframe::new(0.0, Vec2::ZERO),
+        Keyframe::new(0.5, Vec2::new(0.0, 50.0)),
+    ]);
+    let piston = spawn_kinematic(
+        &mut sim,
+        Vec2::new(20.0, 100.0),
+        Vec2::ZERO,
+        KinematicObject::default().with_path(path),
+    );
+
+    sim.step(64);
+    assert!(position(&sim, piston).distance(Vec2::new(0.0, 50.0)) < 1e-3);
+    assert_eq!(kinematic_velocity(&sim, piston), Vec2::ZERO);
+}
+
+#[test]
+fn rising_platform_lifts_a_box_without_slowing() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    let platform = spawn_kinematic(
+        &mut sim,
+        Vec2::new(200.0, 20.0),
+        Vec2::ZERO,
+        KinematicObject::new(Vec2::new(0.0, 100.0)),
+    );
+    let crate_ = spawn_box(&mut sim, Vec2::splat(40.0), 50.0, Vec2::new(0.0, 30.0));
+
+    sim.step(128);
+    assert_eq!(kinematic_velocity(&sim, platform), Vec2::new(0.0, 100.0));
+    assert!((position(&sim, platform).y - 200.0).abs() < 1e-2);
+    let gap = position(&sim, crate_).y - position(&sim, platform).y;
+    assert!(
+        (gap - 30.0).abs() < 1.0,
+        "box should ride on top, gap {gap}"
+    );
+    assert!((velocity(&sim, crate_).y - 100.0).abs() < 5.0);
+}
+
+#[test]
+fn sliding_platform_carries_a_box_along_by_friction() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    spawn_kinematic(
+        &mut sim,
+        Vec2::new(2000.0, 20.0),
+        Vec2::ZERO,
+        KinematicObject::new(Vec2::new(150.0, 0.0)),
+    );
+    let crate_ = spawn_box(&mut sim, Vec2::splat(40.0), 5.0, Vec2::new(0.0, 30.0));
+
+    sim.step(128);
+    assert!((velocity(&sim, crate_).x - 150.0).abs() < 5.0);
+    assert!(position(&sim, crate_).y > 25.0);
+}
+
+#[test]
+fn spinning_paddle_knocks_a_ball_away() {
+    let mut sim = weightless();
+    let paddle = spawn_kinematic(
+        &mut sim,
+        Vec2::new(200.0, 20.0),
+        Vec2::ZERO,
+        KinematicObject::default().with_angular_velocity(4.0),
+    );
+    // Waiting a little way off the paddle's far end, where it comes round at 360 units a second.
+    let ball = spawn_ball(&mut sim, 10.0, 5.0, Vec2::new(0.0, 90.0));
+
+    sim.step(64);
+    let speed = velocity(&sim, ball).length();
+    assert!(speed > 300.0, "ball should be flung, speed {speed}");
+    let away = position(&sim, ball).length();
+    assert!(
+        away > 200.0,
+        "ball should leave the paddle, distance {away}"
+    );
+    let paddle = sim.world().get::<KinematicObject>(paddle).unwrap();
+    assert_eq!(paddle.angular_velocity, 4.0);
+}
+
+#[test]
+fn platform_starting_to_move_wakes_what_sleeps_on_it() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    let platform = spawn_kinematic(
+        &mut sim,
+        Vec2::new(200.0, 20.0),
+        Vec2::ZERO,
+        KinematicObject::default(),
+    );
+    let crate_ = spawn_box(&mut sim, Vec2::splat(40.0), 5.0, Vec2::new(0.0, 30.0));
+    sim.step(128);
+    assert!(is_sleeping(&sim, crate_));
+
+    sim.world_mut()
+        .get_mut::<KinematicObject>(platform)
+        .unwrap()
+        .velocity = Vec2::new(0.0, 60.0);
+    sim.step(64);
+    assert!(!is_sleeping(&sim, crate_));
+    assert!(position(&sim, crate_).y > 85.0);
+}
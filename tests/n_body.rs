@@ -0,0 +1,75 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use physics_project::{HeadlessSimulation, Integrator, NBodyGravity, NBodyMethod, PhysicsPlugin};
+
+const G: f32 = 1.0e6;
+
+fn attracting(method: NBodyMethod) -> HeadlessSimulation {
+    HeadlessSimulation::new(
+        PhysicsPlugin::default()
+            .with_gravity(Vec2::ZERO)
+            // Second order, and keeps orbits from slowly spiralling.
+            .with_integrator(Integrator::VelocityVerlet)
+            .with_n_body_gravity(NBodyGravity::new(G).with_method(method)),
+    )
+}
+
+#[test]
+fn pull_follows_the_inverse_square_law() {
+    for distance in [100.0, 200.0] {
+        let mut sim = attracting(NBodyMethod::Direct);
+        let light = spawn_ball(&mut sim, 10.0, 1.0, Vec2::ZERO);
+        let heavy = spawn_ball(&mut sim, 10.0, 50.0, Vec2::new(distance, 0.0));
+
+        sim.step(1);
+        let dt = sim.timestep().as_secs_f32();
+        let expected = G * 50.0 / (distance * distance) * dt;
+        assert!((velocity(&sim, light).x - expected).abs() < expected * 1e-3);
+        // Equal and opposite forces.
+        assert!((velocity(&sim, heavy).x * 50.0 + velocity(&sim, light).x).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn two_bodies_keep_a_circular_orbit() {
+    for method in [NBodyMethod::Direct, NBodyMethod::BarnesHut { theta: 0.5 }] {
+        let mut sim = attracting(method);
+        let (star_mass, planet_mass, radius) = (100.0, 1.0, 300.0);
+        let total = star_mass + planet_mass;
+        // Both circle the centre of mass at the origin.
+        let star = spawn_ball(
+            &mut sim,
+            20.0,
+            star_mass,
+            Vec2::new(-radius * planet_mass / total, 0.0),
+        );
+        let planet = spawn_ball(
+            &mut sim,
+            5.0,
+            planet_mass,
+            Vec2::new(radius * star_mass / total, 0.0),
+        );
+        let speed = (G * total / radius).sqrt();
+        set_velocity(&mut sim, star, Vec2::new(0.0, -speed * planet_mass / total));
+        set_velocity(&mut sim, planet, Vec2::new(0.0, speed * star_mass / total));
+        let start = position(&sim, planet);
+
+        let period = std::f32::consts::TAU * radius / speed;
+        let steps = (period / sim.timestep().as_secs_f32()).round() as u32;
+        for _ in 0..steps {
+            sim.step(1);
+            let separation = position(&sim, planet).distance(position(&sim, star));
+            assert!(
+                (separation - radius).abs() < radius * 0.01,
+                "{method:?}: {separation}"
+            );
+        }
+        assert!(
+            position(&sim, planet).distance(start) < radius * 0.05,
+            "{method:?}: planet ended at {} after one orbit from {start}",
+            position(&sim, planet)
+        );
+    }
+}
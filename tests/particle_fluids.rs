@@ -0,0 +1,111 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use physics_project::{
+    HeadlessSimulation, ParticleFluid, PhysicsPlugin,
+    components::{FluidParticle, Shape, StaticObject},
+};
+
+/// A container of three `Shape::Rect`s: a floor with its top at y = 0 and walls rising 200
+/// units above it, `width` apart on the inside.
+fn spawn_container(sim: &mut HeadlessSimulation, width: f32) {
+    for (shape, position) in [
+        (Shape::Rect(width + 40.0, 20.0), Vec2::new(0.0, -10.0)),
+        (
+            Shape::Rect(20.0, 220.0),
+            Vec2::new(-width / 2.0 - 10.0, 90.0),
+        ),
+        (
+            Shape::Rect(20.0, 220.0),
+            Vec2::new(width / 2.0 + 10.0, 90.0),
+        ),
+    ] {
+        sim.world_mut().spawn((
+            shape,
+            Transform::from_translation(position.extend(0.0)),
+            StaticObject {},
+        ));
+    }
+}
+
+/// Spawns a block of drops `columns` by `rows`, spaced to start at rest, with its bottom-left
+/// drop at `corner`.
+fn spawn_block(
+    sim: &mut HeadlessSimulation,
+    corner: Vec2,
+    columns: usize,
+    rows: usize,
+) -> Vec<Entity> {
+    let spacing = ParticleFluid::default().rest_density.sqrt().recip();
+    (0..rows)
+        .flat_map(|row| (0..columns).map(move |column| (column, row)))
+        .map(|(column, row)| {
+            let position = corner + Vec2::new(column as f32, row as f32) * spacing;
+            sim.world_mut()
+                .spawn((
+                    FluidParticle::default(),
+                    Transform::from_translation(position.extend(0.0)),
+                ))
+                .id()
+        })
+        .collect()
+}
+
+fn particle(sim: &HeadlessSimulation, drop: Entity) -> FluidParticle {
+    *sim.world().get::<FluidParticle>(drop).unwrap()
+}
+
+fn bounds(positions: &[Vec2]) -> (Vec2, Vec2) {
+    positions.iter().fold(
+        (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY)),
+        |(min, max), position| (min.min(*position), max.max(*position)),
+    )
+}
+
+#[test]
+fn column_of_fluid_spreads_across_the_container_floor() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    spawn_container(&mut sim, 200.0);
+    let drops = spawn_block(&mut sim, Vec2::new(-95.0, 5.0), 12, 20);
+
+    sim.step(320);
+    let positions: Vec<_> = drops.iter().map(|drop| position(&sim, *drop)).collect();
+    let (min, max) = bounds(&positions);
+    // Held in by the walls and floor, 2 units in for the drops' radius.
+    assert!(
+        min.x >= -98.5 && max.x <= 98.5 && min.y >= 1.5,
+        "{min} {max}"
+    );
+    // The 124 unit tall column has slumped into a layer covering the floor.
+    assert!(min.x < -90.0 && max.x > 90.0, "{min} {max}");
+    assert!(max.y < 80.0, "{max}");
+
+    // Water barely squashes, so it settles at about its rest density.
+    let rest_density = ParticleFluid::default().rest_density;
+    let density = drops
+        .iter()
+        .map(|drop| particle(&sim, *drop).density)
+        .sum::<f32>()
+        / drops.len() as f32;
+    assert!(
+        (density - rest_density).abs() < rest_density * 0.1,
+        "{density}"
+    );
+}
+
+#[test]
+fn poured_fluid_fills_the_cup() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    spawn_container(&mut sim, 100.0);
+    let drops = spawn_block(&mut sim, Vec2::new(-20.0, 300.0), 8, 10);
+
+    sim.step(128);
+    for drop in &drops {
+        let position = position(&sim, *drop);
+        assert!(
+            position.x.abs() < 50.0 && position.y > 0.0 && position.y < 100.0,
+            "{position}"
+        );
+    }
+}
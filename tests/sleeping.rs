@@ -0,0 +1,114 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use physics_project::{
+    HeadlessSimulation, Integrator, PhysicsForces, PhysicsPlugin,
+    components::{DynamicObject, ForceLabel},
+};
+
+/// A ball dropped onto the floor and left long enough to settle and fall asleep.
+fn sleeping_ball(sim: &mut HeadlessSimulation) -> Entity {
+    spawn_floor(sim);
+    let ball = spawn_ball(sim, 50.0, 5.0, Vec2::new(0.0, -400.0));
+    sim.step(256);
+    assert!(is_sleeping(sim, ball), "{}", velocity(sim, ball));
+    ball
+}
+
+#[test]
+fn ball_dropped_on_the_floor_falls_asleep_and_holds_still() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    let ball = sleeping_ball(&mut sim);
+    assert!((position(&sim, ball).y + 425.0).abs() < 1.0);
+
+    let resting = position(&sim, ball);
+    sim.step(64);
+    assert_eq!(position(&sim, ball), resting);
+    assert_eq!(velocity(&sim, ball), Vec2::ZERO);
+}
+
+#[test]
+fn every_integrator_lets_a_ball_fall_asleep_and_hold_still() {
+    for integrator in [
+        Integrator::SemiImplicitEuler,
+        Integrator::VelocityVerlet,
+        Integrator::Rk4,
+    ] {
+        let mut sim = HeadlessSimulation::new(PhysicsPlugin::default().with_integrator(integrator));
+        let ball = sleeping_ball(&mut sim);
+
+        let resting = position(&sim, ball);
+        sim.step(64);
+        assert!(is_sleeping(&sim, ball), "{integrator:?}");
+        assert_eq!(position(&sim, ball), resting, "{integrator:?}");
+        assert_eq!(velocity(&sim, ball), Vec2::ZERO, "{integrator:?}");
+        // Only this step's gravity, however often the integrator reran the forces.
+        let dynamic_object = sim.world().get::<DynamicObject>(ball).unwrap();
+        let gravity = dynamic_object
+            .forces
+            .iter()
+            .filter(|force| force.label == ForceLabel::Gravity)
+            .count();
+        assert_eq!(gravity, 1, "{integrator:?}");
+    }
+}
+
+#[test]
+fn ball_stays_awake_with_sleeping_off() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default().with_sleeping(false));
+    spawn_floor(&mut sim);
+    let ball = spawn_ball(&mut sim, 50.0, 5.0, Vec2::new(0.0, -400.0));
+    sim.step(256);
+    assert!(!is_sleeping(&sim, ball));
+}
+
+#[test]
+fn impulse_wakes_a_sleeping_ball() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    let ball = sleeping_ball(&mut sim);
+
+    sim.world_mut()
+        .get_mut::<DynamicObject>(ball)
+        .unwrap()
+        .add_impulse(Vec2::new(1000.0, 0.0));
+    let start = position(&sim, ball);
+    sim.step(4);
+    assert!(!is_sleeping(&sim, ball));
+    assert!(position(&sim, ball).x > start.x + 5.0);
+}
+
+#[derive(Component)]
+struct Pushed;
+
+#[test]
+fn applied_force_wakes_a_sleeping_ball() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    let ball = sleeping_ball(&mut sim);
+
+    sim.world_mut().entity_mut(ball).insert(Pushed);
+    sim.app_mut().add_systems(
+        PhysicsForces,
+        |mut pushed: Query<&mut DynamicObject, With<Pushed>>| {
+            for mut dynamic_object in &mut pushed {
+                dynamic_object.add_force(Vec2::new(5000.0, 0.0));
+            }
+        },
+    );
+    let start = position(&sim, ball);
+    sim.step(16);
+    assert!(!is_sleeping(&sim, ball));
+    assert!(position(&sim, ball).x > start.x + 5.0);
+}
+
+#[test]
+fn falling_ball_wakes_the_one_it_lands_on() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default());
+    let bottom = sleeping_ball(&mut sim);
+
+    let top = spawn_ball(&mut sim, 50.0, 5.0, Vec2::new(30.0, -100.0));
+    let start = position(&sim, bottom);
+    sim.step(64);
+    assert!(position(&sim, bottom).x < start.x - 1.0);
+    assert!(position(&sim, top).x > 30.0);
+}
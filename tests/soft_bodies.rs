@@ -0,0 +1,174 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use physics_project::{
+    HeadlessSimulation, PhysicsPlugin, SoftBodyBuilder,
+    components::{PhysicsMaterial, Shape, SoftBody, StaticObject},
+};
+
+fn spawn(sim: &mut HeadlessSimulation, builder: SoftBodyBuilder, position: Vec2) -> SoftBody {
+    let body = builder.spawn(&mut sim.world_mut().commands(), position);
+    sim.world_mut().flush();
+    sim.world().get::<SoftBody>(body).unwrap().clone()
+}
+
+/// The floor from [`spawn_floor`], but without any bounce, which would otherwise set each point
+/// of a soft body bouncing on its own.
+fn dead_floor(sim: &mut HeadlessSimulation) {
+    let floor = spawn_floor(sim);
+    sim.world_mut()
+        .entity_mut(floor)
+        .insert(PhysicsMaterial::new(0.0, 0.5, 0.3));
+}
+
+fn positions(sim: &HeadlessSimulation, body: &SoftBody) -> Vec<Vec2> {
+    body.particles
+        .iter()
+        .map(|particle| position(sim, *particle))
+        .collect()
+}
+
+#[test]
+fn rectangle_is_a_braced_grid_of_colliding_points() {
+    let mut sim = weightless();
+    let body = spawn(
+        &mut sim,
+        SoftBodyBuilder::rectangle(Vec2::new(40.0, 40.0), 3, 3),
+        Vec2::ZERO,
+    );
+
+    assert_eq!(body.particles.len(), 9);
+    // 12 along the edges of the cells, 8 across them and 6 bending.
+    assert_eq!(body.springs.len(), 26);
+    assert_eq!(body.triangles.len(), 8);
+    assert!(
+        body.particles
+            .iter()
+            .all(|particle| sim.world().get::<Shape>(*particle).is_some())
+    );
+
+    // Starting at rest length, nothing moves.
+    sim.step(64);
+    for particle in &body.particles {
+        assert!(velocity(&sim, *particle).length() < 1e-3);
+    }
+}
+
+#[test]
+fn dropped_box_lands_on_the_floor_and_keeps_its_shape() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default().with_sleeping(false));
+    dead_floor(&mut sim);
+    let body = spawn(
+        &mut sim,
+        SoftBodyBuilder::rectangle(Vec2::new(100.0, 60.0), 6, 4),
+        Vec2::new(0.0, -300.0),
+    );
+
+    sim.step(320);
+    let points = positions(&sim, &body);
+    let (min, max) = points.iter().fold(
+        (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY)),
+        |(min, max), point| (min.min(*point), max.max(*point)),
+    );
+    // The bottom row of circles rests on the floor's top at y = -475.
+    assert!(min.y > -475.0, "{min}");
+    assert!(min.y < -465.0, "{min}");
+    let size = max - min;
+    assert!((size.x - 100.0).abs() < 20.0, "{size}");
+    assert!(size.y > 30.0, "{size}");
+}
+
+#[test]
+fn circle_bounces_back_into_a_disc() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default().with_sleeping(false));
+    dead_floor(&mut sim);
+    let body = spawn(
+        &mut sim,
+        SoftBodyBuilder::circle(50.0, 2, 12),
+        Vec2::new(0.0, -380.0),
+    );
+
+    sim.step(320);
+    let points = positions(&sim, &body);
+    let middle = points[0];
+    for rim in &points[13..] {
+        assert!(
+            (rim.distance(middle) - 50.0).abs() < 15.0,
+            "{}",
+            rim.distance(middle)
+        );
+    }
+}
+
+#[test]
+fn rope_drapes_over_a_ledge() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default().with_sleeping(false));
+    sim.world_mut().spawn((
+        Shape::Rect(100.0, 20.0),
+        Transform::default(),
+        StaticObject {},
+    ));
+    let body = spawn(
+        &mut sim,
+        SoftBodyBuilder::rope(300.0, 15),
+        Vec2::new(0.0, 50.0),
+    );
+
+    sim.step(320);
+    let points = positions(&sim, &body);
+    // The middle rests on the ledge while the ends hang down either side.
+    assert!(points[7].y > 0.0 && points[8].y > 0.0, "{points:?}");
+    assert!(points[0].y < -50.0 && points[15].y < -50.0, "{points:?}");
+    assert!(points[5].x < -50.0 && points[10].x > 50.0, "{points:?}");
+}
+
+#[test]
+fn zero_radius_points_land_without_spinning() {
+    let mut sim = HeadlessSimulation::new(PhysicsPlugin::default().with_sleeping(false));
+    dead_floor(&mut sim);
+    let body = spawn(
+        &mut sim,
+        SoftBodyBuilder::rectangle(Vec2::new(100.0, 60.0), 6, 4).with_particle_radius(0.0),
+        Vec2::new(0.0, -300.0),
+    );
+    for particle in &body.particles {
+        set_velocity(&mut sim, *particle, Vec2::new(200.0, 0.0));
+    }
+
+    // The bottom row drags along the floor, but bare points have nothing to turn.
+    sim.step(320);
+    for particle in &body.particles {
+        assert!(position(&sim, *particle).is_finite());
+        assert_eq!(rotation(&sim, *particle), 0.0);
+    }
+}
+
+#[test]
+fn despawned_points_leave_the_body_and_its_triangles() {
+    let mut sim = weightless();
+    let entity = SoftBodyBuilder::rectangle(Vec2::new(40.0, 40.0), 3, 3)
+        .spawn(&mut sim.world_mut().commands(), Vec2::ZERO);
+    sim.world_mut().flush();
+    let body = sim.world().get::<SoftBody>(entity).unwrap().clone();
+    // The middle point is in every one of the 8 triangles but the two cut off at the corners
+    // its diagonals don't reach.
+    let middle = body.particles[4];
+    sim.world_mut().despawn(middle);
+
+    sim.step(1);
+    let pruned = sim.world().get::<SoftBody>(entity).unwrap();
+    let mut kept = body.particles.clone();
+    kept.retain(|&particle| particle != middle);
+    assert_eq!(pruned.particles, kept);
+    assert_eq!(pruned.triangles.len(), 2);
+    // Each remaining triangle still names the same points it did before.
+    for triangle in &pruned.triangles {
+        let points = triangle.map(|i| pruned.particles[i as usize]);
+        assert!(
+            body.triangles
+                .iter()
+                .any(|old| { old.map(|i| body.particles[i as usize]) == points })
+        );
+    }
+}